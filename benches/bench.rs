@@ -4,7 +4,7 @@ use std::io::{BufRead, BufReader};
 
 use binggan::plugins::{BPUTrasher, CacheTrasher};
 use binggan::{BenchRunner, PeakMemAlloc, INSTRUMENTED_SYSTEM};
-use serde_json_borrow::OwnedValue;
+use serde_json_borrow::{OwnedValue, Value};
 
 #[global_allocator]
 pub static GLOBAL: &PeakMemAlloc<std::alloc::System> = &INSTRUMENTED_SYSTEM;
@@ -89,6 +89,53 @@ fn parse_bench() {
             },
         );
 
+        runner.register("serde_json_borrow::ValueBuffer", move |_data| {
+            let mut buffer = serde_json_borrow::ValueBuffer::new();
+            for line in input_gen() {
+                let json: &Value = buffer.parse_str(&line).unwrap();
+                black_box(json);
+            }
+        });
+
+        runner.register("serde_json_borrow::from_str", move |_data| {
+            for line in input_gen() {
+                let json: Value = serde_json_borrow::from_str(&line).unwrap();
+                black_box(json);
+            }
+        });
+
+        runner.register(
+            "serde_json_borrow::from_str + access by key",
+            move |_data| {
+                let mut total_size = 0;
+                for line in input_gen() {
+                    let json: Value = serde_json_borrow::from_str(&line).unwrap();
+                    total_size += access_json_borrowed(&json, access);
+                }
+                black_box(total_size);
+            },
+        );
+
+        #[cfg(feature = "simd")]
+        runner.register("serde_json_borrow::SimdParser", move |_data| {
+            let mut parser = serde_json_borrow::SimdParser::new();
+            for line in input_gen() {
+                let mut data: Vec<u8> = line.into();
+                let json: Value = parser.parse(&mut data).unwrap();
+                black_box(json);
+            }
+        });
+
+        #[cfg(feature = "bumpalo")]
+        runner.register("serde_json_borrow::ArenaValue", move |_data| {
+            let mut arena = bumpalo::Bump::new();
+            for line in input_gen() {
+                let json = serde_json_borrow::ArenaValue::from_str_in(&line, &arena).unwrap();
+                black_box(json);
+                arena.reset();
+            }
+        });
+
         runner.register("SIMD_json_borrow", move |_data| {
             for line in input_gen() {
                 let mut data: Vec<u8> = line.into();
@@ -186,11 +233,11 @@ fn access_json(el: &serde_json::Value, access: &[&[&str]]) -> usize {
     total_size
 }
 
-fn access_json_borrowed(el: &OwnedValue, access: &[&[&str]]) -> usize {
+fn access_json_borrowed(el: &Value, access: &[&[&str]]) -> usize {
     let mut total_size = 0;
     for access in access {
         // walk the access keys until the end. return 0 if value does not exist
-        let mut val = el.get_value();
+        let mut val = el;
         for key in *access {
             val = val.get(*key);
         }
@@ -0,0 +1,289 @@
+use core::fmt;
+use std::borrow::Cow;
+use std::fmt::Debug;
+
+use bumpalo::Bump;
+
+use crate::index::Index;
+use crate::num::Number;
+use crate::object_vec::ObjectAsVec;
+use crate::parser::{from_utf8, Parser};
+use crate::{Error, Value};
+
+/// A JSON value whose arrays and objects are slices in a [`bumpalo::Bump`] arena.
+///
+/// This requires the `bumpalo` feature flag. A [`Value`] allocates a `Vec` for every array
+/// and object, an `ArenaValue` allocates them in the arena instead. Dropping an `ArenaValue`
+/// is free, and the memory of a document is released at once by resetting or dropping the
+/// arena, so the arena can be reused for the next document.
+///
+/// Strings and keys are borrowed from the input. Strings and keys containing escape sequences
+/// are unescaped into the arena.
+///
+/// ## Example
+/// ```
+/// use bumpalo::Bump;
+/// use serde_json_borrow::ArenaValue;
+///
+/// let mut arena = Bump::new();
+/// for line in [r#"{"id": 1, "tags": ["a"]}"#, r#"{"id": 2, "tags": []}"#] {
+///     let value = ArenaValue::from_str_in(line, &arena).unwrap();
+///     assert!(value.get("id").as_u64().is_some());
+///     assert!(value.get("tags").as_array().is_some());
+///     arena.reset();
+/// }
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ArenaValue<'a> {
+    /// Represents a JSON null value.
+    #[default]
+    Null,
+    /// Represents a JSON boolean.
+    Bool(bool),
+    /// Represents a JSON number, whether integer or floating point.
+    Number(Number<'a>),
+    /// Represents a JSON string.
+    Str(&'a str),
+    /// Represents a JSON array.
+    Array(&'a [ArenaValue<'a>]),
+    /// Represents a JSON object, as a slice of entries in the order of the input.
+    ///
+    /// Like [`ObjectAsVec`], duplicate keys are kept and lookups return the first entry.
+    Object(&'a [(&'a str, ArenaValue<'a>)]),
+}
+
+/// Scratch stacks for the elements of the arrays and objects that are being parsed.
+#[derive(Default)]
+pub(crate) struct ArenaScratch<'a> {
+    pub(crate) values: Vec<ArenaValue<'a>>,
+    pub(crate) entries: Vec<(&'a str, ArenaValue<'a>)>,
+}
+
+impl<'a> ArenaValue<'a> {
+    /// Parses a JSON string into an `ArenaValue`, allocating its arrays and objects in `arena`.
+    ///
+    /// The syntax is checked like in [`from_str`](crate::from_str), including the reported
+    /// position of errors and the nesting limit. Like there, duplicate keys in objects are kept.
+    pub fn from_str_in(json: &'a str, arena: &'a Bump) -> Result<Self, Error> {
+        let mut parser = Parser::new(json);
+        let value = parser.parse_arena_value(arena, &mut ArenaScratch::default())?;
+        parser.end()?;
+        Ok(value)
+    }
+
+    /// Validates `&[u8]` for utf-8 and parses it into an `ArenaValue`, allocating its arrays and
+    /// objects in `arena`.
+    ///
+    /// See [`ArenaValue::from_str_in`].
+    pub fn from_slice_in(json: &'a [u8], arena: &'a Bump) -> Result<Self, Error> {
+        Self::from_str_in(from_utf8(json)?, arena)
+    }
+
+    /// Index into an array or object using the syntax `value.get(0)` or `value.get("k")`, like
+    /// [`Value::get`].
+    ///
+    /// Returns `ArenaValue::Null` if the type of `self` does not match the type of the index, or
+    /// if the key or index does not exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bumpalo::Bump;
+    /// # use serde_json_borrow::ArenaValue;
+    /// #
+    /// let arena = Bump::new();
+    /// let data = ArenaValue::from_str_in(r#"{"x": {"y": ["z", "zz"]}}"#, &arena).unwrap();
+    ///
+    /// assert_eq!(data.get("x").get("y").get(0), &ArenaValue::Str("z"));
+    /// assert_eq!(data.get("x").get("y").get(2), &ArenaValue::Null);
+    /// assert_eq!(data.get("a").get("b"), &ArenaValue::Null);
+    /// ```
+    #[inline]
+    pub fn get<I: Index>(&self, index: I) -> &'a ArenaValue<'a> {
+        static NULL: ArenaValue = ArenaValue::Null;
+        index.index_into_arena(self).unwrap_or(&NULL)
+    }
+
+    /// Returns true if `ArenaValue` is ArenaValue::Null.
+    pub fn is_null(&self) -> bool {
+        matches!(self, ArenaValue::Null)
+    }
+
+    /// If the ArenaValue is an Array, returns an iterator over the elements in the array.
+    pub fn iter_array(&self) -> Option<impl Iterator<Item = &'a ArenaValue<'a>>> {
+        self.as_array().map(|values| values.iter())
+    }
+
+    /// If the ArenaValue is an Object, returns an iterator over the elements in the object.
+    pub fn iter_object(&self) -> Option<impl Iterator<Item = (&'a str, &'a ArenaValue<'a>)>> {
+        self.as_object()
+            .map(|entries| entries.iter().map(|(key, value)| (*key, value)))
+    }
+
+    /// If the ArenaValue is an Array, returns the associated slice. Returns None otherwise.
+    pub fn as_array(&self) -> Option<&'a [ArenaValue<'a>]> {
+        match *self {
+            ArenaValue::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// If the ArenaValue is an Object, returns the associated entries. Returns None otherwise.
+    pub fn as_object(&self) -> Option<&'a [(&'a str, ArenaValue<'a>)]> {
+        match *self {
+            ArenaValue::Object(entries) => Some(entries),
+            _ => None,
+        }
+    }
+
+    /// If the ArenaValue is a Boolean, returns the associated bool. Returns None otherwise.
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            ArenaValue::Bool(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// If the ArenaValue is a String, returns the associated str. Returns None otherwise.
+    pub fn as_str(&self) -> Option<&'a str> {
+        match *self {
+            ArenaValue::Str(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// If the ArenaValue is an integer, represent it as i64 if possible. Returns None otherwise.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            ArenaValue::Number(n) => n.as_i64(),
+            _ => None,
+        }
+    }
+
+    /// If the ArenaValue is an integer, represent it as u64 if possible. Returns None otherwise.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            ArenaValue::Number(n) => n.as_u64(),
+            _ => None,
+        }
+    }
+
+    /// If the ArenaValue is a number, represent it as f64 if possible. Returns None otherwise.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            ArenaValue::Number(n) => n.as_f64(),
+            _ => None,
+        }
+    }
+}
+
+impl Debug for ArenaValue<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArenaValue::Null => formatter.write_str("Null"),
+            ArenaValue::Bool(boolean) => write!(formatter, "Bool({})", boolean),
+            ArenaValue::Number(number) => Debug::fmt(number, formatter),
+            ArenaValue::Str(string) => write!(formatter, "Str({:?})", string),
+            ArenaValue::Array(values) => {
+                formatter.write_str("Array ")?;
+                Debug::fmt(values, formatter)
+            }
+            ArenaValue::Object(entries) => {
+                formatter.write_str("Object ")?;
+                Debug::fmt(entries, formatter)
+            }
+        }
+    }
+}
+
+/// Copies the arrays and objects out of the arena, strings stay borrowed.
+impl<'a> From<ArenaValue<'a>> for Value<'a> {
+    fn from(value: ArenaValue<'a>) -> Self {
+        match value {
+            ArenaValue::Null => Value::Null,
+            ArenaValue::Bool(b) => Value::Bool(b),
+            ArenaValue::Number(n) => Value::Number(n),
+            ArenaValue::Str(s) => Value::Str(Cow::Borrowed(s)),
+            ArenaValue::Array(values) => {
+                Value::Array(values.iter().map(|&value| value.into()).collect())
+            }
+            ArenaValue::Object(entries) => Value::Object(ObjectAsVec::from_entries(
+                entries
+                    .iter()
+                    .map(|&(key, value)| (key.into(), value.into()))
+                    .collect(),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_as_value() {
+        let arena = Bump::new();
+        for json in [
+            "null",
+            "true",
+            "-1",
+            "1.5e3",
+            r#""a\"b😀""#,
+            "[]",
+            "{}",
+            r#"{"a": {"b": [null, true, {"c": "d"}]}, "e\n": -1, "f": [], "a": 2}"#,
+        ] {
+            let value = ArenaValue::from_str_in(json, &arena).unwrap();
+            assert_eq!(
+                Value::from(value),
+                crate::from_str(json).unwrap(),
+                "{}",
+                json
+            );
+        }
+    }
+
+    #[test]
+    fn test_accessors() {
+        let arena = Bump::new();
+        let json = r#"{"a": [1, "x\ty"], "b": {"c": false}, "a": null}"#;
+        let value = ArenaValue::from_str_in(json, &arena).unwrap();
+        assert_eq!(value.get("a").get(0).as_u64(), Some(1));
+        assert_eq!(value.get("a").get(1).as_str(), Some("x\ty"));
+        assert_eq!(value.get("b").get("c").as_bool(), Some(false));
+        assert!(value.get("a").get(2).is_null());
+        assert!(value.get(0).is_null());
+        assert_eq!(value.get("a").iter_array().unwrap().count(), 2);
+        let keys: Vec<&str> = value.iter_object().unwrap().map(|(key, _)| key).collect();
+        assert_eq!(keys, vec!["a", "b", "a"]);
+        assert_eq!(value.as_object().unwrap().len(), 3);
+        assert!(value.as_array().is_none());
+    }
+
+    #[test]
+    fn test_strings_are_borrowed() {
+        let arena = Bump::new();
+        let json = r#"["abc", "a\nb"]"#;
+        let value = ArenaValue::from_str_in(json, &arena).unwrap();
+        let borrowed = value.get(0).as_str().unwrap();
+        assert!(json.as_bytes().as_ptr_range().contains(&borrowed.as_ptr()));
+        let unescaped = value.get(1).as_str().unwrap();
+        assert_eq!(unescaped, "a\nb");
+        assert!(arena.allocated_bytes() > 0);
+    }
+
+    #[test]
+    fn test_errors() {
+        let arena = Bump::new();
+        let err = ArenaValue::from_str_in("[1,\n 2,]", &arena).unwrap_err();
+        assert_eq!(err.to_string(), "trailing comma at line 2 column 4");
+        let err = ArenaValue::from_str_in("[1] 2", &arena).unwrap_err();
+        assert_eq!(err.message(), "trailing characters");
+        let err = ArenaValue::from_slice_in(b"\"\xff\"", &arena).unwrap_err();
+        assert_eq!(err.message(), "invalid UTF-8");
+        let json = "[".repeat(129) + &"]".repeat(129);
+        let err = ArenaValue::from_str_in(&json, &arena).unwrap_err();
+        assert_eq!(err.message(), "recursion limit exceeded");
+    }
+}
@@ -1,4 +1,4 @@
-use std::borrow::Cow;
+use std::borrow::{Borrow, Cow};
 use std::ops::Deref;
 
 use serde::Deserialize;
@@ -8,8 +8,30 @@ use serde::Deserialize;
 ///
 /// This is because serde always deserializes strings into `Cow::Owned`.
 /// https://github.com/serde-rs/serde/issues/1852#issuecomment-559517427
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
-pub struct CowStr<'a>(#[serde(borrow)] pub Cow<'a, str>);
+///
+/// Without the `cowkeys` feature flag only borrowed keys are accepted during deserialization,
+/// which does not allow escaped data in keys. Keys can still be owned, e.g. when they are
+/// created by [`crate::to_value`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "cowkeys", derive(Deserialize))]
+pub struct CowStr<'a>(#[cfg_attr(feature = "cowkeys", serde(borrow))] pub Cow<'a, str>);
+
+#[cfg(not(feature = "cowkeys"))]
+impl<'de: 'a, 'a> Deserialize<'de> for CowStr<'a> {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where D: serde::Deserializer<'de> {
+        <&'de str>::deserialize(deserializer).map(CowStr::from)
+    }
+}
+
+impl CowStr<'_> {
+    /// Converts the key into an owned key, which is no longer tied to the lifetime of the input.
+    #[inline]
+    pub fn into_owned(self) -> CowStr<'static> {
+        CowStr(Cow::Owned(self.0.into_owned()))
+    }
+}
 
 impl Deref for CowStr<'_> {
     type Target = str;
@@ -19,6 +41,12 @@ impl Deref for CowStr<'_> {
     }
 }
 
+impl Borrow<str> for CowStr<'_> {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
 impl PartialEq<&str> for CowStr<'_> {
     fn eq(&self, other: &&str) -> bool {
         self.0 == *other
@@ -31,6 +59,18 @@ impl<'a> From<&'a str> for CowStr<'a> {
     }
 }
 
+impl<'a> From<Cow<'a, str>> for CowStr<'a> {
+    fn from(s: Cow<'a, str>) -> Self {
+        Self(s)
+    }
+}
+
+impl From<String> for CowStr<'_> {
+    fn from(s: String) -> Self {
+        Self(Cow::Owned(s))
+    }
+}
+
 impl<'a> From<CowStr<'a>> for Cow<'a, str> {
     fn from(s: CowStr<'a>) -> Self {
         s.0
@@ -1,140 +1,292 @@
 // use crate::error::Error;
 use core::fmt;
 use std::borrow::Cow;
+use std::collections::HashMap;
 
-use serde::de::{Deserialize, MapAccess, SeqAccess, Visitor};
+use serde::de::{self, Deserialize, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde_json::value::RawValue;
 
-use crate::object_vec::ObjectAsVec;
+use crate::object_vec::{KeyStrType, ObjectAsVec};
+use crate::ownedvalue::OwnedValue;
 use crate::value::Value;
 
+/// Number of entries from which duplicate keys are detected via a hash map instead of comparing
+/// all keys.
+const INDEX_THRESHOLD: usize = 32;
+
+/// Defines how keys occurring more than once in the same JSON object are handled during
+/// deserialization.
+///
+/// The default, [`DuplicateKeyPolicy::KeepAll`], is what the `Deserialize` implementation of
+/// [`Value`] does. Other policies can be selected by using the policy as a
+/// [`DeserializeSeed`], or via the `*_with_policy` constructors of
+/// [`OwnedValue`](crate::OwnedValue).
+///
+/// ## Example
+/// ```
+/// use serde::de::DeserializeSeed;
+/// use serde_json_borrow::{DuplicateKeyPolicy, Value};
+///
+/// let json = r#"{"a": 1, "a": 2}"#;
+/// let mut deserializer = serde_json::Deserializer::from_str(json);
+/// let value = DuplicateKeyPolicy::KeepLast.deserialize(&mut deserializer).unwrap();
+/// assert_eq!(value.get("a"), &Value::from(2u64));
+/// assert_eq!(value.as_object().unwrap().len(), 1);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DuplicateKeyPolicy {
+    /// Keep all entries, including the duplicates. Lookups by key return the first entry.
+    ///
+    /// This is the fastest policy, since keys don't need to be compared during deserialization.
+    #[default]
+    KeepAll,
+    /// Keep the first entry and ignore later entries with the same key.
+    KeepFirst,
+    /// Keep the value of the last entry with the same key, at the position of the first entry.
+    ///
+    /// This matches the behavior of `serde_json::Value`.
+    KeepLast,
+    /// Fail deserialization if a key occurs more than once.
+    Error,
+}
+
+impl<'de> DeserializeSeed<'de> for DuplicateKeyPolicy {
+    type Value = Value<'de>;
+
+    #[inline]
+    fn deserialize<D>(self, deserializer: D) -> Result<Value<'de>, D::Error>
+    where D: serde::Deserializer<'de> {
+        deserializer.deserialize_any(ValueVisitor { policy: self })
+    }
+}
+
 impl<'de> Deserialize<'de> for Value<'de> {
     #[inline]
     fn deserialize<D>(deserializer: D) -> Result<Value<'de>, D::Error>
     where D: serde::Deserializer<'de> {
-        struct ValueVisitor;
+        DuplicateKeyPolicy::KeepAll.deserialize(deserializer)
+    }
+}
 
-        impl<'de> Visitor<'de> for ValueVisitor {
-            type Value = Value<'de>;
+/// Captures the JSON text of the value as a [`RawValue`] and parses it with
+/// [`from_str`](crate::from_str), which allows to keep a JSON subtree of an owned message, e.g.
+/// one that is read via `serde_json::from_reader`.
+///
+/// This only works with the deserializers of `serde_json`, other formats can't provide the raw
+/// JSON text.
+impl<'de> Deserialize<'de> for OwnedValue {
+    fn deserialize<D>(deserializer: D) -> Result<OwnedValue, D::Error>
+    where D: serde::Deserializer<'de> {
+        let raw = Box::<RawValue>::deserialize(deserializer)?;
+        let json: Box<str> = raw.into();
+        OwnedValue::parse_with(json.into(), crate::from_str).map_err(de::Error::custom)
+    }
+}
 
-            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                formatter.write_str("any valid JSON value")
-            }
+/// Adds the entries of an object to a Vec, applying a [`DuplicateKeyPolicy`].
+pub(crate) struct EntryCollector<'ctx> {
+    policy: DuplicateKeyPolicy,
+    /// The positions of the keys, built once the object has `INDEX_THRESHOLD` entries.
+    positions: Option<HashMap<KeyStrType<'ctx>, usize>>,
+}
 
-            #[inline]
-            fn visit_bool<E>(self, value: bool) -> Result<Value<'de>, E> {
-                Ok(Value::Bool(value))
-            }
+impl<'ctx> EntryCollector<'ctx> {
+    pub(crate) fn new(policy: DuplicateKeyPolicy) -> Self {
+        Self {
+            policy,
+            positions: None,
+        }
+    }
 
-            #[inline]
-            fn visit_i64<E>(self, value: i64) -> Result<Value<'de>, E> {
-                Ok(Value::Number(value.into()))
+    /// Adds the entry to `entries`, or handles it as duplicate according to the policy. Returns
+    /// the key as error if it is a duplicate and the policy is [`DuplicateKeyPolicy::Error`].
+    #[inline]
+    pub(crate) fn push(
+        &mut self,
+        entries: &mut Vec<(KeyStrType<'ctx>, Value<'ctx>)>,
+        key: KeyStrType<'ctx>,
+        value: Value<'ctx>,
+    ) -> Result<(), KeyStrType<'ctx>> {
+        if self.policy == DuplicateKeyPolicy::KeepAll {
+            entries.push((key, value));
+            return Ok(());
+        }
+        if self.positions.is_none() && entries.len() >= INDEX_THRESHOLD {
+            // Avoid quadratic lookups for large objects. The keys in `entries` are unique.
+            let positions = entries
+                .iter()
+                .enumerate()
+                .map(|(pos, (k, _))| (k.clone(), pos));
+            self.positions = Some(positions.collect());
+        }
+        let pos = match &self.positions {
+            Some(positions) => positions.get(&*key).copied(),
+            None => entries.iter().position(|(k, _)| *k == key),
+        };
+        match pos {
+            None => {
+                if let Some(positions) = &mut self.positions {
+                    positions.insert(key.clone(), entries.len());
+                }
+                entries.push((key, value));
             }
+            Some(_) if self.policy == DuplicateKeyPolicy::KeepFirst => {}
+            Some(pos) if self.policy == DuplicateKeyPolicy::KeepLast => entries[pos].1 = value,
+            Some(_) => return Err(key),
+        }
+        Ok(())
+    }
+}
 
-            #[inline]
-            fn visit_u64<E>(self, value: u64) -> Result<Value<'de>, E> {
-                Ok(Value::Number(value.into()))
-            }
+/// Visitor building a [`Value`], applying the duplicate key policy to objects.
+struct ValueVisitor {
+    policy: DuplicateKeyPolicy,
+}
 
-            #[inline]
-            fn visit_f64<E>(self, value: f64) -> Result<Value<'de>, E> {
-                Ok(Value::Number(value.into()))
-            }
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value<'de>;
 
-            #[inline]
-            fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
-            where E: serde::de::Error {
-                Ok(Value::Str(v.into()))
-            }
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any valid JSON value")
+    }
 
-            #[inline]
-            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
-            where E: serde::de::Error {
-                Ok(Value::Str(Cow::Owned(v.to_owned())))
-            }
+    #[inline]
+    fn visit_bool<E>(self, value: bool) -> Result<Value<'de>, E> {
+        Ok(Value::Bool(value))
+    }
 
-            #[inline]
-            fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
-            where E: serde::de::Error {
-                Ok(Value::Str(Cow::Borrowed(v)))
-            }
+    #[inline]
+    fn visit_i64<E>(self, value: i64) -> Result<Value<'de>, E> {
+        Ok(Value::Number(value.into()))
+    }
 
-            #[inline]
-            fn visit_none<E>(self) -> Result<Value<'de>, E> {
-                Ok(Value::Null)
-            }
+    #[inline]
+    fn visit_u64<E>(self, value: u64) -> Result<Value<'de>, E> {
+        Ok(Value::Number(value.into()))
+    }
 
-            #[inline]
-            fn visit_i8<E>(self, v: i8) -> Result<Self::Value, E> {
-                Ok(Value::Number((v as i64).into()))
-            }
+    #[inline]
+    fn visit_i128<E>(self, value: i128) -> Result<Value<'de>, E> {
+        Ok(Value::Number(value.into()))
+    }
 
-            #[inline]
-            fn visit_i16<E>(self, v: i16) -> Result<Self::Value, E> {
-                Ok(Value::Number((v as i64).into()))
-            }
+    #[inline]
+    fn visit_u128<E>(self, value: u128) -> Result<Value<'de>, E> {
+        Ok(Value::Number(value.into()))
+    }
 
-            #[inline]
-            fn visit_i32<E>(self, v: i32) -> Result<Self::Value, E> {
-                Ok(Value::Number((v as i64).into()))
-            }
+    #[inline]
+    fn visit_f64<E>(self, value: f64) -> Result<Value<'de>, E> {
+        Ok(Value::from(value))
+    }
 
-            #[inline]
-            fn visit_u8<E>(self, v: u8) -> Result<Self::Value, E> {
-                Ok(Value::Number((v as u64).into()))
-            }
+    #[inline]
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where E: de::Error {
+        Ok(Value::Str(v.into()))
+    }
 
-            #[inline]
-            fn visit_u16<E>(self, v: u16) -> Result<Self::Value, E> {
-                Ok(Value::Number((v as u64).into()))
-            }
+    #[inline]
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where E: de::Error {
+        Ok(Value::Str(Cow::Owned(v.to_owned())))
+    }
 
-            #[inline]
-            fn visit_u32<E>(self, v: u32) -> Result<Self::Value, E> {
-                Ok(Value::Number((v as u64).into()))
-            }
+    #[inline]
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+    where E: de::Error {
+        Ok(Value::Str(Cow::Borrowed(v)))
+    }
 
-            #[inline]
-            fn visit_f32<E>(self, v: f32) -> Result<Self::Value, E> {
-                Ok(Value::Number((v as f64).into()))
-            }
+    #[inline]
+    fn visit_none<E>(self) -> Result<Value<'de>, E> {
+        Ok(Value::Null)
+    }
 
-            #[inline]
-            fn visit_some<D>(self, deserializer: D) -> Result<Value<'de>, D::Error>
-            where D: serde::Deserializer<'de> {
-                Deserialize::deserialize(deserializer)
-            }
+    #[inline]
+    fn visit_i8<E>(self, v: i8) -> Result<Self::Value, E> {
+        Ok(Value::Number((v as i64).into()))
+    }
 
-            #[inline]
-            fn visit_unit<E>(self) -> Result<Value<'de>, E> {
-                Ok(Value::Null)
-            }
+    #[inline]
+    fn visit_i16<E>(self, v: i16) -> Result<Self::Value, E> {
+        Ok(Value::Number((v as i64).into()))
+    }
 
-            #[inline]
-            fn visit_seq<V>(self, mut visitor: V) -> Result<Value<'de>, V::Error>
-            where V: SeqAccess<'de> {
-                let mut vec = Vec::with_capacity(visitor.size_hint().unwrap_or(0));
+    #[inline]
+    fn visit_i32<E>(self, v: i32) -> Result<Self::Value, E> {
+        Ok(Value::Number((v as i64).into()))
+    }
 
-                while let Some(elem) = visitor.next_element()? {
-                    vec.push(elem);
-                }
+    #[inline]
+    fn visit_u8<E>(self, v: u8) -> Result<Self::Value, E> {
+        Ok(Value::Number((v as u64).into()))
+    }
+
+    #[inline]
+    fn visit_u16<E>(self, v: u16) -> Result<Self::Value, E> {
+        Ok(Value::Number((v as u64).into()))
+    }
+
+    #[inline]
+    fn visit_u32<E>(self, v: u32) -> Result<Self::Value, E> {
+        Ok(Value::Number((v as u64).into()))
+    }
+
+    #[inline]
+    fn visit_f32<E>(self, v: f32) -> Result<Self::Value, E> {
+        Ok(Value::from(v as f64))
+    }
+
+    #[inline]
+    fn visit_some<D>(self, deserializer: D) -> Result<Value<'de>, D::Error>
+    where D: serde::Deserializer<'de> {
+        self.policy.deserialize(deserializer)
+    }
 
-                Ok(Value::Array(vec))
+    #[inline]
+    fn visit_unit<E>(self) -> Result<Value<'de>, E> {
+        Ok(Value::Null)
+    }
+
+    #[inline]
+    fn visit_seq<V>(self, mut visitor: V) -> Result<Value<'de>, V::Error>
+    where V: SeqAccess<'de> {
+        let mut vec = Vec::with_capacity(visitor.size_hint().unwrap_or(0));
+
+        if self.policy == DuplicateKeyPolicy::KeepAll {
+            while let Some(elem) = visitor.next_element()? {
+                vec.push(elem);
+            }
+        } else {
+            while let Some(elem) = visitor.next_element_seed(self.policy)? {
+                vec.push(elem);
             }
+        }
 
-            #[inline]
-            fn visit_map<V>(self, mut visitor: V) -> Result<Value<'de>, V::Error>
-            where V: MapAccess<'de> {
-                let mut values = Vec::with_capacity(visitor.size_hint().unwrap_or(0));
+        Ok(Value::Array(vec))
+    }
 
-                while let Some((key, value)) = visitor.next_entry()? {
-                    values.push((key, value));
-                }
+    #[inline]
+    fn visit_map<V>(self, mut visitor: V) -> Result<Value<'de>, V::Error>
+    where V: MapAccess<'de> {
+        let mut values = Vec::with_capacity(visitor.size_hint().unwrap_or(0));
 
-                Ok(Value::Object(ObjectAsVec(values)))
+        if self.policy == DuplicateKeyPolicy::KeepAll {
+            while let Some((key, value)) = visitor.next_entry()? {
+                values.push((key, value));
             }
+            return Ok(Value::Object(ObjectAsVec::from_entries(values)));
         }
 
-        deserializer.deserialize_any(ValueVisitor)
+        let mut collector = EntryCollector::new(self.policy);
+        while let Some(key) = visitor.next_key::<KeyStrType<'de>>()? {
+            let value = visitor.next_value_seed(self.policy)?;
+            if let Err(key) = collector.push(&mut values, key, value) {
+                return Err(de::Error::custom(format_args!("duplicate key `{}`", &*key)));
+            }
+        }
+        Ok(Value::Object(ObjectAsVec::from_entries(values)))
     }
 }
 
@@ -143,7 +295,46 @@ mod tests {
 
     use std::borrow::Cow;
 
-    use crate::Value;
+    use serde::de::DeserializeSeed;
+    use serde::Deserialize;
+
+    use super::DuplicateKeyPolicy;
+    use crate::{OwnedValue, Value};
+
+    #[test]
+    fn deserialize_owned_value() {
+        #[derive(Deserialize)]
+        struct Message {
+            id: u64,
+            payload: OwnedValue,
+            extra: Option<OwnedValue>,
+        }
+        let json = r#"{"id": 1, "payload": {"a": ["b", {"c\"": null}]}, "extra": null}"#;
+        let message: Message = serde_json::from_reader(json.as_bytes()).unwrap();
+        assert_eq!(message.id, 1);
+        assert_eq!(
+            message.payload.get_value(),
+            &crate::from_str(r#"{"a": ["b", {"c\"": null}]}"#).unwrap()
+        );
+        assert!(message.extra.is_none());
+        assert_eq!(
+            serde_json::to_string(&message.payload).unwrap(),
+            r#"{"a":["b",{"c\"":null}]}"#
+        );
+
+        let values: Vec<OwnedValue> = serde_json::from_str(r#"[1, "a", []]"#).unwrap();
+        assert_eq!(values[1].as_str(), Some("a"));
+        assert!(serde_json::from_str::<OwnedValue>("[1,]").is_err());
+    }
+
+    #[test]
+    fn deserialize_int128() {
+        use serde::de::value::{Error, I128Deserializer, U128Deserializer};
+        let val = Value::deserialize(U128Deserializer::<Error>::new(u128::MAX));
+        assert_eq!(val.unwrap().as_u128(), Some(u128::MAX));
+        let val = Value::deserialize(I128Deserializer::<Error>::new(i128::MIN));
+        assert_eq!(val.unwrap().as_i128(), Some(i128::MIN));
+    }
 
     #[cfg(feature = "cowkeys")]
     #[test]
@@ -202,4 +393,69 @@ mod tests {
             &Value::Str(Cow::Borrowed("string\"_val"))
         );
     }
+
+    fn deserialize_with_policy(
+        json: &str,
+        policy: DuplicateKeyPolicy,
+    ) -> serde_json::Result<Value<'_>> {
+        policy.deserialize(&mut serde_json::Deserializer::from_str(json))
+    }
+
+    #[test]
+    fn duplicate_key_policy() {
+        let json = r#"{"a": 1, "b": {"c": 1, "c": 2}, "a": 3}"#;
+        let keys = |val: &Value| {
+            val.as_object()
+                .unwrap()
+                .keys()
+                .map(str::to_owned)
+                .collect::<Vec<_>>()
+        };
+
+        let val = deserialize_with_policy(json, DuplicateKeyPolicy::KeepAll).unwrap();
+        assert_eq!(keys(&val), vec!["a", "b", "a"]);
+        assert_eq!(val, serde_json::from_str::<Value>(json).unwrap());
+
+        let val = deserialize_with_policy(json, DuplicateKeyPolicy::KeepFirst).unwrap();
+        assert_eq!(keys(&val), vec!["a", "b"]);
+        assert_eq!(val["a"], Value::from(1u64));
+        assert_eq!(val["b"]["c"], Value::from(1u64));
+        assert_eq!(keys(&val["b"]), vec!["c"]);
+
+        let val = deserialize_with_policy(json, DuplicateKeyPolicy::KeepLast).unwrap();
+        assert_eq!(keys(&val), vec!["a", "b"]);
+        assert_eq!(val["a"], Value::from(3u64));
+        assert_eq!(val["b"]["c"], Value::from(2u64));
+        let expected: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(serde_json::Value::from(val), expected);
+
+        let err = deserialize_with_policy(json, DuplicateKeyPolicy::Error).unwrap_err();
+        assert!(err.to_string().starts_with("duplicate key `c`"), "{}", err);
+        let err = deserialize_with_policy(r#"[{"a": 1, "a": 1}]"#, DuplicateKeyPolicy::Error);
+        assert!(err.is_err());
+        assert!(
+            deserialize_with_policy(r#"[{"a": 1}, {"a": 1}]"#, DuplicateKeyPolicy::Error).is_ok()
+        );
+    }
+
+    #[test]
+    fn duplicate_key_policy_large_object() {
+        let mut json = String::from("{");
+        for i in 0..100 {
+            json.push_str(&format!(r#""k{}": {}, "#, i % 50, i));
+        }
+        json.push_str(r#""last": null}"#);
+
+        let val = deserialize_with_policy(&json, DuplicateKeyPolicy::KeepLast).unwrap();
+        let obj = val.as_object().unwrap();
+        assert_eq!(obj.len(), 51);
+        #[cfg(feature = "key_index")]
+        assert!(!obj.has_index());
+        assert_eq!(obj.get_key_value_at(0), Some(("k0", &Value::from(50u64))));
+        assert_eq!(obj.get("k49"), Some(&Value::from(99u64)));
+
+        let val = deserialize_with_policy(&json, DuplicateKeyPolicy::KeepFirst).unwrap();
+        assert_eq!(val["k49"], Value::from(49u64));
+        assert!(deserialize_with_policy(&json, DuplicateKeyPolicy::Error).is_err());
+    }
 }
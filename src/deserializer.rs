@@ -1,10 +1,15 @@
-use serde::de::{self, IntoDeserializer, MapAccess, SeqAccess, Visitor};
+use std::borrow::Cow;
+
+use serde::de::{
+    self, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, Unexpected, VariantAccess, Visitor,
+};
 use serde::Deserializer;
 
-use crate::num::N;
+use crate::error::Error;
+use crate::num::{Number, N};
 use crate::{KeyStrType, Value};
 
-impl<'de> IntoDeserializer<'de, de::value::Error> for &'de Value<'_> {
+impl<'de> IntoDeserializer<'de, Error> for &'de Value<'_> {
     type Deserializer = Self;
 
     fn into_deserializer(self) -> Self::Deserializer {
@@ -13,18 +18,14 @@ impl<'de> IntoDeserializer<'de, de::value::Error> for &'de Value<'_> {
 }
 
 impl<'de> Deserializer<'de> for &'de Value<'_> {
-    type Error = de::value::Error;
+    type Error = Error;
 
     fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where V: Visitor<'de> {
         match self {
             Value::Null => visitor.visit_unit(),
             Value::Bool(b) => visitor.visit_bool(*b),
-            Value::Number(n) => match n.n {
-                N::PosInt(u) => visitor.visit_u64(u),
-                N::NegInt(i) => visitor.visit_i64(i),
-                N::Float(f) => visitor.visit_f64(f),
-            },
+            Value::Number(n) => n.visit(visitor),
             Value::Str(s) => visitor.visit_borrowed_str(s),
             Value::Array(arr) => {
                 let seq = SeqDeserializer::new(arr);
@@ -62,6 +63,11 @@ impl<'de> Deserializer<'de> for &'de Value<'_> {
         self.deserialize_any(visitor)
     }
 
+    fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        self.deserialize_any(visitor)
+    }
+
     fn deserialize_u8<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where V: Visitor<'de> {
         self.deserialize_any(visitor)
@@ -82,6 +88,11 @@ impl<'de> Deserializer<'de> for &'de Value<'_> {
         self.deserialize_any(visitor)
     }
 
+    fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        self.deserialize_any(visitor)
+    }
+
     fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where V: Visitor<'de> {
         self.deserialize_any(visitor)
@@ -172,12 +183,40 @@ impl<'de> Deserializer<'de> for &'de Value<'_> {
         self,
         _name: &'static str,
         _variants: &'static [&'static str],
-        _visitor: V,
+        visitor: V,
     ) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        Err(de::Error::custom("deserialize_enum is not yet supported"))
+        match self {
+            // Unit variants are represented as a plain string, e.g. `"Variant"`
+            Value::Str(variant) => visitor.visit_enum(EnumDeserializer {
+                variant,
+                value: None,
+            }),
+            // All other variants are represented as an object with a single key, e.g.
+            // `{"Variant": ...}`
+            Value::Object(map) => {
+                let mut iter = map.iter();
+                let (variant, value) = match (iter.next(), iter.next()) {
+                    (Some(entry), None) => entry,
+                    _ => {
+                        return Err(de::Error::invalid_value(
+                            Unexpected::Map,
+                            &"map with a single key",
+                        ))
+                    }
+                };
+                visitor.visit_enum(EnumDeserializer {
+                    variant,
+                    value: Some(value),
+                })
+            }
+            other => Err(de::Error::invalid_type(
+                other.unexpected(),
+                &"string or map",
+            )),
+        }
     }
 
     fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value, Self::Error>
@@ -216,23 +255,25 @@ impl<'de> Deserializer<'de> for &'de Value<'_> {
 
 // Helper struct to deserialize sequences (arrays).
 struct SeqDeserializer<'a, 'ctx> {
-    iter: std::slice::Iter<'a, Value<'ctx>>,
+    iter: std::iter::Enumerate<std::slice::Iter<'a, Value<'ctx>>>,
 }
 
 impl<'a, 'ctx> SeqDeserializer<'a, 'ctx> {
     fn new(slice: &'a [Value<'ctx>]) -> Self {
-        SeqDeserializer { iter: slice.iter() }
+        SeqDeserializer {
+            iter: slice.iter().enumerate(),
+        }
     }
 }
 
 impl<'de, 'a: 'de, 'ctx: 'de> SeqAccess<'de> for SeqDeserializer<'a, 'ctx> {
-    type Error = de::value::Error;
+    type Error = Error;
 
     fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
     where T: de::DeserializeSeed<'de> {
         self.iter
             .next()
-            .map(|value| seed.deserialize(value))
+            .map(|(index, value)| seed.deserialize(value).map_err(|err| err.with_index(index)))
             .transpose()
     }
 }
@@ -240,7 +281,7 @@ impl<'de, 'a: 'de, 'ctx: 'de> SeqAccess<'de> for SeqDeserializer<'a, 'ctx> {
 // Helper struct to deserialize maps (objects).
 struct MapDeserializer<'a, 'ctx> {
     iter: std::slice::Iter<'a, (KeyStrType<'ctx>, Value<'ctx>)>,
-    value: Option<&'a Value<'ctx>>,
+    value: Option<(&'a str, &'a Value<'ctx>)>,
 }
 
 impl<'a, 'ctx> MapDeserializer<'a, 'ctx> {
@@ -253,14 +294,15 @@ impl<'a, 'ctx> MapDeserializer<'a, 'ctx> {
 }
 
 impl<'de, 'a: 'de, 'ctx: 'de> MapAccess<'de> for MapDeserializer<'a, 'ctx> {
-    type Error = de::value::Error;
+    type Error = Error;
 
     fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
     where K: de::DeserializeSeed<'de> {
         if let Some((key, value)) = self.iter.next() {
-            self.value = Some(value);
+            self.value = Some((key, value));
             seed.deserialize(de::value::BorrowedStrDeserializer::new(key))
                 .map(Some)
+                .map_err(|err: Error| err.with_key(key))
         } else {
             Ok(None)
         }
@@ -269,20 +311,634 @@ impl<'de, 'a: 'de, 'ctx: 'de> MapAccess<'de> for MapDeserializer<'a, 'ctx> {
     fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
     where V: de::DeserializeSeed<'de> {
         match self.value.take() {
-            Some(value) => seed.deserialize(value),
+            Some((key, value)) => seed
+                .deserialize(value)
+                .map_err(|err: Error| err.with_key(key)),
             None => Err(de::Error::custom("value is missing")),
         }
     }
 }
 
+// Helper struct to deserialize enums, either from a string or a single key object.
+struct EnumDeserializer<'a, 'ctx> {
+    variant: &'a str,
+    value: Option<&'a Value<'ctx>>,
+}
+
+impl<'de, 'a: 'de, 'ctx: 'de> EnumAccess<'de> for EnumDeserializer<'a, 'ctx> {
+    type Error = Error;
+    type Variant = VariantDeserializer<'a, 'ctx>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+    where V: de::DeserializeSeed<'de> {
+        let variant = seed.deserialize(de::value::BorrowedStrDeserializer::new(self.variant))?;
+        Ok((
+            variant,
+            VariantDeserializer {
+                variant: self.variant,
+                value: self.value,
+            },
+        ))
+    }
+}
+
+// Helper struct to deserialize the content of an enum variant.
+struct VariantDeserializer<'a, 'ctx> {
+    variant: &'a str,
+    value: Option<&'a Value<'ctx>>,
+}
+
+impl<'de, 'a: 'de, 'ctx: 'de> VariantAccess<'de> for VariantDeserializer<'a, 'ctx> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        match self.value {
+            Some(value) => {
+                de::Deserialize::deserialize(value).map_err(|err| err.with_key(self.variant))
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+    where T: de::DeserializeSeed<'de> {
+        match self.value {
+            Some(value) => seed
+                .deserialize(value)
+                .map_err(|err| err.with_key(self.variant)),
+            None => Err(de::Error::invalid_type(
+                Unexpected::UnitVariant,
+                &"newtype variant",
+            )),
+        }
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        match self.value {
+            Some(Value::Array(arr)) => visitor
+                .visit_seq(SeqDeserializer::new(arr))
+                .map_err(|err| err.with_key(self.variant)),
+            Some(other) => Err(<Error as de::Error>::invalid_type(
+                other.unexpected(),
+                &"tuple variant",
+            )
+            .with_key(self.variant)),
+            None => Err(de::Error::invalid_type(
+                Unexpected::UnitVariant,
+                &"tuple variant",
+            )),
+        }
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Some(Value::Object(map)) => visitor
+                .visit_map(MapDeserializer::new(map.as_vec().as_slice()))
+                .map_err(|err| err.with_key(self.variant)),
+            Some(other) => Err(<Error as de::Error>::invalid_type(
+                other.unexpected(),
+                &"struct variant",
+            )
+            .with_key(self.variant)),
+            None => Err(de::Error::invalid_type(
+                Unexpected::UnitVariant,
+                &"struct variant",
+            )),
+        }
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for Value<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+/// Deserializes by value. Owned strings are moved into the target via `visit_string`, borrowed
+/// strings are passed on via `visit_borrowed_str`, so no string data is copied.
+impl<'de> Deserializer<'de> for Value<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        match self {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(b) => visitor.visit_bool(b),
+            Value::Number(n) => n.visit(visitor),
+            Value::Str(Cow::Borrowed(s)) => visitor.visit_borrowed_str(s),
+            Value::Str(Cow::Owned(s)) => visitor.visit_string(s),
+            Value::Array(arr) => {
+                let seq = OwnedSeqDeserializer::new(arr);
+                visitor.visit_seq(seq)
+            }
+            Value::Object(map) => {
+                let map = OwnedMapDeserializer::new(map.0);
+                visitor.visit_map(map)
+            }
+        }
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_i8<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_i16<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_i32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_i64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_u8<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_u16<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_u32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_u64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_f64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_char<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        match self {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            // Unit variants are represented as a plain string, e.g. `"Variant"`
+            Value::Str(variant) => visitor.visit_enum(OwnedEnumDeserializer {
+                variant,
+                value: None,
+            }),
+            // All other variants are represented as an object with a single key, e.g.
+            // `{"Variant": ...}`
+            Value::Object(map) => {
+                let mut iter = map.0.into_iter();
+                let (variant, value) = match (iter.next(), iter.next()) {
+                    (Some(entry), None) => entry,
+                    _ => {
+                        return Err(de::Error::invalid_value(
+                            Unexpected::Map,
+                            &"map with a single key",
+                        ))
+                    }
+                };
+                visitor.visit_enum(OwnedEnumDeserializer {
+                    variant: variant.into(),
+                    value: Some(value),
+                })
+            }
+            other => Err(de::Error::invalid_type(
+                other.unexpected(),
+                &"string or map",
+            )),
+        }
+    }
+
+    fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+}
+
+// Helper struct to deserialize owned sequences (arrays).
+struct OwnedSeqDeserializer<'de> {
+    iter: std::iter::Enumerate<std::vec::IntoIter<Value<'de>>>,
+}
+
+impl<'de> OwnedSeqDeserializer<'de> {
+    fn new(vec: Vec<Value<'de>>) -> Self {
+        OwnedSeqDeserializer {
+            iter: vec.into_iter().enumerate(),
+        }
+    }
+}
+
+impl<'de> SeqAccess<'de> for OwnedSeqDeserializer<'de> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where T: de::DeserializeSeed<'de> {
+        self.iter
+            .next()
+            .map(|(index, value)| seed.deserialize(value).map_err(|err| err.with_index(index)))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+// Helper struct to deserialize owned maps (objects).
+struct OwnedMapDeserializer<'de> {
+    iter: std::vec::IntoIter<(KeyStrType<'de>, Value<'de>)>,
+    // The key is kept to be able to report it in the error path of the value.
+    value: Option<(Cow<'de, str>, Value<'de>)>,
+}
+
+impl<'de> OwnedMapDeserializer<'de> {
+    fn new(map: Vec<(KeyStrType<'de>, Value<'de>)>) -> Self {
+        OwnedMapDeserializer {
+            iter: map.into_iter(),
+            value: None,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for OwnedMapDeserializer<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where K: de::DeserializeSeed<'de> {
+        if let Some((key, value)) = self.iter.next() {
+            let key: Cow<'de, str> = key.into();
+            let result = seed
+                .deserialize(KeyDeserializer {
+                    key: Cow::Borrowed(&key),
+                })
+                .map(Some)
+                .map_err(|err| err.with_key(&key));
+            self.value = Some((key, value));
+            result
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where V: de::DeserializeSeed<'de> {
+        match self.value.take() {
+            Some((key, value)) => seed.deserialize(value).map_err(|err| err.with_key(&key)),
+            None => Err(de::Error::custom("value is missing")),
+        }
+    }
+
+    // Deserializes the value first, so the key can afterwards be moved into the key visitor
+    // instead of being kept around for the error path.
+    fn next_entry_seed<K, V>(
+        &mut self,
+        kseed: K,
+        vseed: V,
+    ) -> Result<Option<(K::Value, V::Value)>, Self::Error>
+    where
+        K: de::DeserializeSeed<'de>,
+        V: de::DeserializeSeed<'de>,
+    {
+        if let Some((key, value)) = self.iter.next() {
+            let key: Cow<'de, str> = key.into();
+            let value = vseed.deserialize(value).map_err(|err| err.with_key(&key))?;
+            let key = kseed.deserialize(KeyDeserializer {
+                key: Cow::Owned(key),
+            })?;
+            Ok(Some((key, value)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+// Helper struct to deserialize owned enums, either from a string or a single key object.
+struct OwnedEnumDeserializer<'de> {
+    variant: Cow<'de, str>,
+    value: Option<Value<'de>>,
+}
+
+impl<'de> EnumAccess<'de> for OwnedEnumDeserializer<'de> {
+    type Error = Error;
+    type Variant = OwnedVariantDeserializer<'de>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+    where V: de::DeserializeSeed<'de> {
+        let variant = seed.deserialize(KeyDeserializer {
+            key: Cow::Borrowed(&self.variant),
+        })?;
+        Ok((
+            variant,
+            OwnedVariantDeserializer {
+                variant: self.variant,
+                value: self.value,
+            },
+        ))
+    }
+}
+
+// Helper struct to deserialize the content of an owned enum variant.
+struct OwnedVariantDeserializer<'de> {
+    variant: Cow<'de, str>,
+    value: Option<Value<'de>>,
+}
+
+impl<'de> VariantAccess<'de> for OwnedVariantDeserializer<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        match self.value {
+            Some(value) => {
+                de::Deserialize::deserialize(value).map_err(|err| err.with_key(&self.variant))
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+    where T: de::DeserializeSeed<'de> {
+        match self.value {
+            Some(value) => seed
+                .deserialize(value)
+                .map_err(|err| err.with_key(&self.variant)),
+            None => Err(de::Error::invalid_type(
+                Unexpected::UnitVariant,
+                &"newtype variant",
+            )),
+        }
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        match self.value {
+            Some(Value::Array(arr)) => visitor
+                .visit_seq(OwnedSeqDeserializer::new(arr))
+                .map_err(|err| err.with_key(&self.variant)),
+            Some(other) => Err(<Error as de::Error>::invalid_type(
+                other.unexpected(),
+                &"tuple variant",
+            )
+            .with_key(&self.variant)),
+            None => Err(de::Error::invalid_type(
+                Unexpected::UnitVariant,
+                &"tuple variant",
+            )),
+        }
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Some(Value::Object(map)) => visitor
+                .visit_map(OwnedMapDeserializer::new(map.0))
+                .map_err(|err| err.with_key(&self.variant)),
+            Some(other) => Err(<Error as de::Error>::invalid_type(
+                other.unexpected(),
+                &"struct variant",
+            )
+            .with_key(&self.variant)),
+            None => Err(de::Error::invalid_type(
+                Unexpected::UnitVariant,
+                &"struct variant",
+            )),
+        }
+    }
+}
+
+// Helper struct to deserialize object keys and enum variant names of owned values.
+//
+// Borrowed keys are passed on via `visit_borrowed_str`. Owned keys are moved into
+// `visit_string` when the key is handed over, and only lent via `visit_str` when the caller
+// still needs the key afterwards for the error path.
+struct KeyDeserializer<'a, 'de> {
+    key: Cow<'a, Cow<'de, str>>,
+}
+
+impl<'de> Deserializer<'de> for KeyDeserializer<'_, 'de> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where V: Visitor<'de> {
+        match self.key {
+            Cow::Borrowed(Cow::Borrowed(key)) => visitor.visit_borrowed_str(key),
+            Cow::Owned(Cow::Borrowed(key)) => visitor.visit_borrowed_str(key),
+            Cow::Borrowed(Cow::Owned(key)) => visitor.visit_str(key),
+            Cow::Owned(Cow::Owned(key)) => visitor.visit_string(key),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+impl Number<'_> {
+    /// Passes the number to `visitor`. Integers which don't fit into 128 bits are passed as
+    /// `f64`.
+    fn visit<'de, V: Visitor<'de>>(&self, visitor: V) -> Result<V::Value, Error> {
+        match self.n {
+            N::PosInt(u) => visitor.visit_u64(u),
+            N::NegInt(i) => visitor.visit_i64(i),
+            N::PosInt128(u) => visitor.visit_u128(u.get()),
+            N::NegInt128(i) => visitor.visit_i128(i.get() as i128),
+            N::Float(f) | N::FloatLexeme(f, _) | N::BigInt(f, _) => visitor.visit_f64(f),
+        }
+    }
+
+    /// Describes the number for error messages.
+    pub(crate) fn unexpected(&self) -> Unexpected<'static> {
+        match self.n {
+            N::PosInt(u) => Unexpected::Unsigned(u),
+            N::NegInt(i) => Unexpected::Signed(i),
+            N::Float(f) | N::FloatLexeme(f, _) => Unexpected::Float(f),
+            N::PosInt128(_) | N::NegInt128(_) | N::BigInt(..) => Unexpected::Other("number"),
+        }
+    }
+}
+
+impl Value<'_> {
+    /// Describes the value for error messages of the `Deserializer`.
+    pub(crate) fn unexpected(&self) -> Unexpected<'_> {
+        match self {
+            Value::Null => Unexpected::Unit,
+            Value::Bool(b) => Unexpected::Bool(*b),
+            Value::Number(n) => n.unexpected(),
+            Value::Str(s) => Unexpected::Str(s),
+            Value::Array(_) => Unexpected::Seq,
+            Value::Object(_) => Unexpected::Map,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use serde::de::value::Error as DeError;
-    use serde::de::{IgnoredAny, IntoDeserializer};
+    use std::borrow::Cow;
+
+    use serde::de::{DeserializeOwned, IgnoredAny, IntoDeserializer};
     use serde::Deserialize;
 
     use crate::num::N;
-    use crate::Value;
+    use crate::{Error, Value};
 
     // Basic deserialization test for null value
     #[test]
@@ -327,6 +983,20 @@ mod tests {
         assert_eq!(deserialized, 42);
     }
 
+    // Test deserialization of 128-bit integer values
+    #[test]
+    fn test_deserialize_int128() {
+        let value = Value::from(u128::MAX);
+        let deserialized: u128 = Deserialize::deserialize(&value).unwrap();
+        assert_eq!(deserialized, u128::MAX);
+        let value = Value::from(i128::MIN);
+        let deserialized: i128 = Deserialize::deserialize(value.clone()).unwrap();
+        assert_eq!(deserialized, i128::MIN);
+        assert!(u64::deserialize(&value).is_err());
+        let deserialized: i128 = Deserialize::deserialize(&Value::from(-42i64)).unwrap();
+        assert_eq!(deserialized, -42);
+    }
+
     // Test deserialization of floating point (f64) value
     #[test]
     fn test_deserialize_f64() {
@@ -454,11 +1124,294 @@ mod tests {
         let _deserialized: IgnoredAny = Deserialize::deserialize(&value).unwrap();
     }
 
-    // Test deserialization failure (for an unsupported type like enum)
+    // Test deserialization failure (a string is not a unit)
     #[test]
     fn test_deserialize_enum_fails() {
         let value = Value::Str("EnumVariant".into());
-        let result: Result<(), DeError> = Deserialize::deserialize(&value);
+        let result: Result<(), Error> = Deserialize::deserialize(&value);
         assert!(result.is_err());
     }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    enum External {
+        Unit,
+        Newtype(u64),
+        Tuple(u64, String),
+        Struct { a: u64, b: Option<bool> },
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(tag = "type")]
+    enum Internal {
+        Unit,
+        Struct { a: u64 },
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(tag = "t", content = "c")]
+    enum Adjacent {
+        Unit,
+        Newtype(String),
+        Tuple(u64, u64),
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(untagged)]
+    enum Untagged {
+        Num(u64),
+        Text(String),
+        Pair { a: u64, b: u64 },
+    }
+
+    /// Deserializes `json` via `&Value` and checks that serde_json produces the same result.
+    fn deserialize_like_serde_json<T>(json: &str) -> T
+    where T: DeserializeOwned + std::fmt::Debug + PartialEq {
+        let value: Value = serde_json::from_str(json).unwrap();
+        let expected: T = serde_json::from_str(json).unwrap();
+        let deserialized = T::deserialize(&value).unwrap();
+        assert_eq!(deserialized, expected);
+        deserialized
+    }
+
+    #[test]
+    fn test_deserialize_enum_external() {
+        assert_eq!(
+            deserialize_like_serde_json::<External>(r#""Unit""#),
+            External::Unit
+        );
+        assert_eq!(
+            deserialize_like_serde_json::<External>(r#"{"Newtype": 5}"#),
+            External::Newtype(5)
+        );
+        assert_eq!(
+            deserialize_like_serde_json::<External>(r#"{"Tuple": [5, "x"]}"#),
+            External::Tuple(5, "x".to_string())
+        );
+        assert_eq!(
+            deserialize_like_serde_json::<External>(r#"{"Struct": {"a": 1, "b": true}}"#),
+            External::Struct {
+                a: 1,
+                b: Some(true)
+            }
+        );
+        assert_eq!(
+            deserialize_like_serde_json::<Vec<External>>(r#"["Unit", {"Newtype": 1}]"#),
+            vec![External::Unit, External::Newtype(1)]
+        );
+    }
+
+    #[test]
+    fn test_deserialize_enum_external_errors() {
+        let invalid = [
+            r#""Unknown""#,
+            r#"{"Newtype": 1, "Unit": null}"#,
+            r#"{}"#,
+            r#"5"#,
+            r#""Newtype""#,
+            r#"{"Tuple": 5}"#,
+            r#"{"Struct": [1]}"#,
+            r#"{"Unit": 1}"#,
+        ];
+        for json in invalid {
+            let value: Value = serde_json::from_str(json).unwrap();
+            assert!(serde_json::from_str::<External>(json).is_err(), "{json}");
+            let result: Result<External, Error> = Deserialize::deserialize(&value);
+            assert!(result.is_err(), "{json}");
+        }
+    }
+
+    #[test]
+    fn test_deserialize_enum_internally_tagged() {
+        assert_eq!(
+            deserialize_like_serde_json::<Internal>(r#"{"type": "Unit"}"#),
+            Internal::Unit
+        );
+        assert_eq!(
+            deserialize_like_serde_json::<Internal>(r#"{"type": "Struct", "a": 3}"#),
+            Internal::Struct { a: 3 }
+        );
+    }
+
+    #[test]
+    fn test_deserialize_enum_adjacently_tagged() {
+        assert_eq!(
+            deserialize_like_serde_json::<Adjacent>(r#"{"t": "Unit"}"#),
+            Adjacent::Unit
+        );
+        assert_eq!(
+            deserialize_like_serde_json::<Adjacent>(r#"{"t": "Newtype", "c": "x"}"#),
+            Adjacent::Newtype("x".to_string())
+        );
+        assert_eq!(
+            deserialize_like_serde_json::<Adjacent>(r#"{"c": [1, 2], "t": "Tuple"}"#),
+            Adjacent::Tuple(1, 2)
+        );
+    }
+
+    #[test]
+    fn test_deserialize_enum_untagged() {
+        assert_eq!(
+            deserialize_like_serde_json::<Untagged>("5"),
+            Untagged::Num(5)
+        );
+        assert_eq!(
+            deserialize_like_serde_json::<Untagged>(r#""five""#),
+            Untagged::Text("five".to_string())
+        );
+        assert_eq!(
+            deserialize_like_serde_json::<Untagged>(r#"{"a": 1, "b": 2}"#),
+            Untagged::Pair { a: 1, b: 2 }
+        );
+    }
+
+    #[test]
+    fn test_error_path() {
+        #[derive(Debug, Deserialize)]
+        struct Item {
+            #[allow(dead_code)]
+            price: u64,
+        }
+
+        #[derive(Debug, Deserialize)]
+        struct Order {
+            #[allow(dead_code)]
+            items: Vec<Item>,
+        }
+
+        let value: Value =
+            serde_json::from_str(r#"{"items": [{"price": 1}, {"price": 2}, {"price": -3}]}"#)
+                .unwrap();
+        let err = Order::deserialize(&value).unwrap_err();
+        assert_eq!(err.path(), ".items[2].price");
+        assert_eq!(err.expected(), Some("u64"));
+        assert_eq!(err.actual(), Some("number"));
+        assert_eq!(
+            err.to_string(),
+            "invalid value: integer `-3`, expected u64 at .items[2].price"
+        );
+
+        let value: Value = serde_json::from_str(r#"{"items": [{}]}"#).unwrap();
+        let err = Order::deserialize(&value).unwrap_err();
+        assert_eq!(err.path(), ".items[0]");
+        assert_eq!(err.to_string(), "missing field `price` at .items[0]");
+        assert_eq!(err.expected(), None);
+
+        let value: Value = serde_json::from_str(r#"{"items": {"price": 1}}"#).unwrap();
+        let err = Order::deserialize(&value).unwrap_err();
+        assert_eq!(err.path(), ".items");
+        assert_eq!(err.expected(), Some("a sequence"));
+        assert_eq!(err.actual(), Some("object"));
+    }
+
+    #[test]
+    fn test_error_path_enum() {
+        let value: Value = serde_json::from_str(r#"[{"Struct": {"a": "1"}}]"#).unwrap();
+        let err = Vec::<External>::deserialize(&value).unwrap_err();
+        assert_eq!(err.path(), "[0].Struct.a");
+        assert_eq!(err.actual(), Some("string"));
+
+        let value: Value = serde_json::from_str(r#"{"Tuple": [1, 2]}"#).unwrap();
+        let err = External::deserialize(&value).unwrap_err();
+        assert_eq!(err.path(), ".Tuple[1]");
+    }
+
+    #[test]
+    fn test_owned_deserialize_moves_owned_strings() {
+        let text = "escaped \"text\"".to_string();
+        let ptr = text.as_ptr();
+        let value = Value::Array(vec![Value::Str(Cow::Owned(text))]);
+        let deserialized: Vec<String> = Deserialize::deserialize(value).unwrap();
+        assert_eq!(deserialized, vec!["escaped \"text\"".to_string()]);
+        // The String was moved, not copied
+        assert_eq!(deserialized[0].as_ptr(), ptr);
+    }
+
+    #[test]
+    fn test_owned_deserialize_borrows_borrowed_strings() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Borrowing<'a> {
+            name: &'a str,
+            tags: Vec<&'a str>,
+            #[serde(borrow)]
+            escaped: Cow<'a, str>,
+        }
+
+        let json = r#"{"name": "John", "tags": ["a", "b"], "escaped": "c\"d"}"#;
+        let value: Value = serde_json::from_str(json).unwrap();
+        let deserialized: Borrowing = Deserialize::deserialize(value).unwrap();
+        assert_eq!(deserialized.name, "John");
+        assert_eq!(deserialized.tags, vec!["a", "b"]);
+        assert!(matches!(&deserialized.escaped, Cow::Owned(text) if text == "c\"d"));
+    }
+
+    #[test]
+    fn test_owned_deserialize_like_serde_json() {
+        fn check<T>(json: &str)
+        where T: DeserializeOwned + std::fmt::Debug + PartialEq {
+            let value: Value = serde_json::from_str(json).unwrap();
+            let expected: T = serde_json::from_str(json).unwrap();
+            let deserialized = T::deserialize(value.into_deserializer()).unwrap();
+            assert_eq!(deserialized, expected);
+        }
+
+        check::<Option<u64>>("null");
+        check::<Option<u64>>("42");
+        check::<(i64, f64, bool)>("[-1, 2.5, true]");
+        check::<std::collections::HashMap<String, Vec<u64>>>(r#"{"a": [1], "b": [2, 3]}"#);
+        check::<External>(r#""Unit""#);
+        check::<External>(r#"{"Newtype": 5}"#);
+        check::<External>(r#"{"Tuple": [5, "x"]}"#);
+        check::<External>(r#"{"Struct": {"a": 1, "b": null}}"#);
+        check::<Internal>(r#"{"type": "Struct", "a": 3}"#);
+        check::<Adjacent>(r#"{"t": "Newtype", "c": "x"}"#);
+        check::<Untagged>(r#"{"a": 1, "b": 2}"#);
+        check::<Untagged>(r#""five""#);
+    }
+
+    #[test]
+    fn test_owned_deserialize_error_path() {
+        let value: Value = serde_json::from_str(r#"{"ab": [{"Struct": {"a": "1"}}]}"#).unwrap();
+        let err =
+            std::collections::HashMap::<String, Vec<External>>::deserialize(value).unwrap_err();
+        assert_eq!(err.path(), ".ab[0].Struct.a");
+        assert_eq!(err.expected(), Some("u64"));
+        assert_eq!(err.actual(), Some("string"));
+    }
+
+    #[test]
+    #[cfg(feature = "cowkeys")]
+    fn test_owned_deserialize_moves_owned_keys() {
+        // Records whether the key was handed over as an owned `String`.
+        #[derive(Debug, PartialEq, Eq, Hash)]
+        struct Key(String, bool);
+
+        impl<'de> Deserialize<'de> for Key {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where D: serde::Deserializer<'de> {
+                struct KeyVisitor;
+
+                impl serde::de::Visitor<'_> for KeyVisitor {
+                    type Value = Key;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                        formatter.write_str("a string")
+                    }
+
+                    fn visit_str<E>(self, v: &str) -> Result<Key, E> {
+                        Ok(Key(v.to_owned(), false))
+                    }
+
+                    fn visit_string<E>(self, v: String) -> Result<Key, E> {
+                        Ok(Key(v, true))
+                    }
+                }
+
+                deserializer.deserialize_string(KeyVisitor)
+            }
+        }
+
+        let value: Value = serde_json::from_str(r#"{"a\"b": 1}"#).unwrap();
+        let map = std::collections::HashMap::<Key, u64>::deserialize(value).unwrap();
+        assert_eq!(map.get(&Key("a\"b".to_owned(), true)), Some(&1));
+    }
 }
@@ -0,0 +1,323 @@
+use std::fmt::{self, Debug, Display};
+use std::io;
+
+use serde::de::{self, Expected, Unexpected};
+use serde::ser;
+
+/// Error returned when deserializing a [`Value`](crate::Value) into another type, or serializing a
+/// type into a [`Value`](crate::Value) via [`to_value`](crate::to_value) fails.
+///
+/// It is also returned by [`from_str`](crate::from_str) for invalid JSON, in which case
+/// [`Error::line`] and [`Error::column`] locate the error in the input.
+///
+/// In addition to the error message, the error records where in the document the failure
+/// happened, as a path like `.items[3].price`, and for type mismatches the expected type and
+/// the kind of the value that was found instead.
+///
+/// # Example
+/// ```
+/// use serde::Deserialize;
+/// use serde_json_borrow::Value;
+///
+/// #[derive(Deserialize)]
+/// struct Item {
+///     price: u64,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Order {
+///     items: Vec<Item>,
+/// }
+///
+/// let value: Value = serde_json::from_str(r#"{"items": [{"price": 1}, {"price": "2"}]}"#).unwrap();
+/// let err = Order::deserialize(&value).err().unwrap();
+/// assert_eq!(err.path(), ".items[1].price");
+/// assert_eq!(err.expected(), Some("u64"));
+/// assert_eq!(err.actual(), Some("string"));
+/// ```
+pub struct Error {
+    // Boxed to keep `Result<T, Error>` small.
+    inner: Box<ErrorImpl>,
+}
+
+struct ErrorImpl {
+    msg: String,
+    /// The path segments, innermost first, since they are added while the error bubbles up.
+    path: Vec<PathSegment>,
+    expected: Option<String>,
+    actual: Option<String>,
+    /// One-based position of a syntax error in the input, 0 if not applicable.
+    line: usize,
+    column: usize,
+}
+
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+impl Error {
+    fn new(msg: String) -> Self {
+        Error {
+            inner: Box::new(ErrorImpl {
+                msg,
+                path: Vec::new(),
+                expected: None,
+                actual: None,
+                line: 0,
+                column: 0,
+            }),
+        }
+    }
+
+    /// The message of the error, without the path.
+    pub fn message(&self) -> &str {
+        &self.inner.msg
+    }
+
+    /// The path to the value that caused the error, e.g. `.items[3].price`.
+    ///
+    /// Returns `.` if the error occurred at the root value.
+    pub fn path(&self) -> String {
+        if self.inner.path.is_empty() {
+            return ".".to_string();
+        }
+        let mut path = String::new();
+        for segment in self.inner.path.iter().rev() {
+            match segment {
+                PathSegment::Key(key) => {
+                    path.push('.');
+                    path.push_str(key);
+                }
+                PathSegment::Index(index) => {
+                    path.push('[');
+                    path.push_str(&index.to_string());
+                    path.push(']');
+                }
+            }
+        }
+        path
+    }
+
+    /// The type that was expected, if the error is caused by a type or value mismatch.
+    pub fn expected(&self) -> Option<&str> {
+        self.inner.expected.as_deref()
+    }
+
+    /// The kind of value that was found, if the error is caused by a type or value mismatch.
+    ///
+    /// JSON values are reported as `null`, `boolean`, `number`, `string`, `array` or `object`.
+    pub fn actual(&self) -> Option<&str> {
+        self.inner.actual.as_deref()
+    }
+
+    /// The one-based line number at which a syntax error occurred, or 0 if the error did not
+    /// occur while parsing JSON text.
+    pub fn line(&self) -> usize {
+        self.inner.line
+    }
+
+    /// The one-based column number at which a syntax error occurred, or 0 if the error did not
+    /// occur while parsing JSON text.
+    ///
+    /// The column counts bytes, and is 0 for errors at the end of an empty line.
+    pub fn column(&self) -> usize {
+        self.inner.column
+    }
+
+    /// Creates an error for invalid JSON text at the given position.
+    pub(crate) fn syntax(msg: &str, line: usize, column: usize) -> Self {
+        let mut err = Error::new(msg.to_string());
+        err.inner.line = line;
+        err.inner.column = column;
+        err
+    }
+
+    /// Moves the position of a syntax error down by `lines` lines.
+    pub(crate) fn offset_line(mut self, lines: usize) -> Self {
+        if self.inner.line != 0 {
+            self.inner.line += lines;
+        }
+        self
+    }
+
+    /// Prepends an object key to the path.
+    pub(crate) fn with_key(mut self, key: &str) -> Self {
+        self.inner.path.push(PathSegment::Key(key.to_string()));
+        self
+    }
+
+    /// Prepends an array index to the path.
+    pub(crate) fn with_index(mut self, index: usize) -> Self {
+        self.inner.path.push(PathSegment::Index(index));
+        self
+    }
+
+    fn mismatch(msg: String, unexp: Unexpected, exp: &dyn Expected) -> Self {
+        let mut err = Error::new(msg);
+        err.inner.expected = Some(exp.to_string());
+        err.inner.actual = Some(unexpected_kind(unexp));
+        err
+    }
+}
+
+/// Maps the serde description of a value to the name of the JSON value kind.
+fn unexpected_kind(unexp: Unexpected) -> String {
+    match unexp {
+        Unexpected::Unit => "null".to_string(),
+        Unexpected::Bool(_) => "boolean".to_string(),
+        Unexpected::Unsigned(_) | Unexpected::Signed(_) | Unexpected::Float(_) => {
+            "number".to_string()
+        }
+        Unexpected::Char(_) | Unexpected::Str(_) => "string".to_string(),
+        Unexpected::Seq => "array".to_string(),
+        Unexpected::Map => "object".to_string(),
+        other => other.to_string(),
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::new(msg.to_string())
+    }
+
+    fn invalid_type(unexp: Unexpected, exp: &dyn Expected) -> Self {
+        let msg = format!("invalid type: {}, expected {}", unexp, exp);
+        Error::mismatch(msg, unexp, exp)
+    }
+
+    fn invalid_value(unexp: Unexpected, exp: &dyn Expected) -> Self {
+        let msg = format!("invalid value: {}, expected {}", unexp, exp);
+        Error::mismatch(msg, unexp, exp)
+    }
+}
+
+impl ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::new(msg.to_string())
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.inner.line != 0 {
+            write!(
+                f,
+                "{} at line {} column {}",
+                self.inner.msg, self.inner.line, self.inner.column
+            )
+        } else if self.inner.path.is_empty() {
+            Display::fmt(&self.inner.msg, f)
+        } else {
+            write!(f, "{} at {}", self.inner.msg, self.path())
+        }
+    }
+}
+
+impl Debug for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.inner.line != 0 {
+            write!(
+                f,
+                "Error({:?}, line: {}, column: {})",
+                self.inner.msg, self.inner.line, self.inner.column
+            )
+        } else {
+            write!(f, "Error({:?}, path: {:?})", self.inner.msg, self.path())
+        }
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(err: Error) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, err)
+    }
+}
+
+/// Error returned when an input is larger than the allowed size, e.g. by
+/// [`OwnedValue::from_reader_with_limit`](crate::OwnedValue::from_reader_with_limit).
+///
+/// It is returned wrapped in an [`io::Error`] of kind [`io::ErrorKind::InvalidData`].
+///
+/// # Example
+/// ```
+/// use serde_json_borrow::{LimitExceeded, OwnedValue};
+///
+/// let err = OwnedValue::from_reader_with_limit(&b"[1, 2, 3]"[..], 4).unwrap_err();
+/// let limit = err.get_ref().and_then(|err| err.downcast_ref::<LimitExceeded>());
+/// assert_eq!(limit.map(LimitExceeded::limit), Some(4));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LimitExceeded {
+    limit: usize,
+}
+
+impl LimitExceeded {
+    pub(crate) fn new(limit: usize) -> Self {
+        LimitExceeded { limit }
+    }
+
+    /// The maximum allowed size in bytes.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+}
+
+impl std::error::Error for LimitExceeded {}
+
+impl Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "input exceeds the size limit of {} bytes", self.limit)
+    }
+}
+
+impl From<LimitExceeded> for io::Error {
+    fn from(err: LimitExceeded) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::de::Error as _;
+
+    use super::*;
+
+    #[test]
+    fn test_path_display() {
+        let err = Error::custom("boom");
+        assert_eq!(err.path(), ".");
+        assert_eq!(err.to_string(), "boom");
+
+        let err = err.with_key("price").with_index(3).with_key("items");
+        assert_eq!(err.path(), ".items[3].price");
+        assert_eq!(err.to_string(), "boom at .items[3].price");
+        assert_eq!(err.message(), "boom");
+    }
+
+    #[test]
+    fn test_expected_actual() {
+        let err = Error::invalid_type(Unexpected::Str("a"), &"u64");
+        assert_eq!(err.expected(), Some("u64"));
+        assert_eq!(err.actual(), Some("string"));
+        assert_eq!(err.to_string(), "invalid type: string \"a\", expected u64");
+
+        let err = Error::custom("boom");
+        assert_eq!(err.expected(), None);
+        assert_eq!(err.actual(), None);
+    }
+
+    #[test]
+    fn test_syntax_display() {
+        let err = Error::syntax("expected value", 2, 5);
+        assert_eq!((err.line(), err.column()), (2, 5));
+        assert_eq!(err.to_string(), "expected value at line 2 column 5");
+        assert_eq!(
+            format!("{:?}", err),
+            "Error(\"expected value\", line: 2, column: 5)"
+        );
+        assert_eq!(Error::custom("boom").line(), 0);
+    }
+}
@@ -1,12 +1,16 @@
 use super::Value;
+#[cfg(feature = "bumpalo")]
+use crate::ArenaValue;
+use crate::ObjectAsVec;
 
 /// A type that can be used to index into a `serde_json_borrow::Value`.
 ///
-/// [`get`] of `Value` accept any type that implements `Index`. This
+/// [`get`] and [`get_mut`] of `Value` accept any type that implements `Index`. This
 /// trait is implemented for strings which are used as the index into a JSON
 /// map, and for `usize` which is used as the index into a JSON array.
 ///
 /// [`get`]: ../enum.Value.html#method.get
+/// [`get_mut`]: ../enum.Value.html#method.get_mut
 ///
 /// This trait is sealed and cannot be implemented for types outside of
 /// `serde_json_borrow`.
@@ -32,28 +36,234 @@ use super::Value;
 /// assert_eq!(data.get("a"), &Value::Null);
 /// assert_eq!(data.get("a").get("b"), &Value::Null);
 /// ```
-pub trait Index<'v> {
+pub trait Index: private::Sealed {
     /// Return None if the key is not already in the array or object.
     #[doc(hidden)]
-    fn index_into(self, v: &'v Value<'v>) -> Option<&'v Value<'v>>;
+    fn index_into<'v, 'ctx>(&self, v: &'v Value<'ctx>) -> Option<&'v Value<'ctx>>;
+
+    /// Return None if the key is not already in the array or object.
+    #[doc(hidden)]
+    fn index_into_mut<'v, 'ctx>(&self, v: &'v mut Value<'ctx>) -> Option<&'v mut Value<'ctx>>;
+
+    /// Panic if array index out of bounds. If key is not already in the object,
+    /// insert it with a value of null. Panic if Value is a type that cannot be
+    /// indexed into, except if Value is null then it can be treated as an empty
+    /// object.
+    #[doc(hidden)]
+    fn index_or_insert<'v, 'ctx>(&self, v: &'v mut Value<'ctx>) -> &'v mut Value<'ctx>;
 }
 
-impl<'v> Index<'v> for usize {
+impl Index for usize {
+    #[inline]
+    fn index_into<'v, 'ctx>(&self, v: &'v Value<'ctx>) -> Option<&'v Value<'ctx>> {
+        match v {
+            Value::Array(vec) => vec.get(*self),
+            _ => None,
+        }
+    }
+
     #[inline]
-    fn index_into(self, v: &'v Value<'v>) -> Option<&'v Value<'v>> {
+    fn index_into_mut<'v, 'ctx>(&self, v: &'v mut Value<'ctx>) -> Option<&'v mut Value<'ctx>> {
         match v {
-            Value::Array(vec) => vec.get(self),
+            Value::Array(vec) => vec.get_mut(*self),
             _ => None,
         }
     }
+
+    fn index_or_insert<'v, 'ctx>(&self, v: &'v mut Value<'ctx>) -> &'v mut Value<'ctx> {
+        match v {
+            Value::Array(vec) => {
+                let len = vec.len();
+                vec.get_mut(*self).unwrap_or_else(|| {
+                    panic!(
+                        "cannot access index {} of JSON array of length {}",
+                        self, len
+                    )
+                })
+            }
+            _ => panic!("cannot access index {} of JSON {}", self, Type(v)),
+        }
+    }
 }
 
-impl<'v, 'a: 'v> Index<'v> for &'a str {
+impl Index for str {
     #[inline]
-    fn index_into(self, v: &'v Value<'v>) -> Option<&'v Value<'v>> {
+    fn index_into<'v, 'ctx>(&self, v: &'v Value<'ctx>) -> Option<&'v Value<'ctx>> {
         match v {
-            Value::Object(map) => map.iter().find(|(k, _v)| k == &self).map(|(_k, v)| v),
+            Value::Object(map) => map.get(self),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn index_into_mut<'v, 'ctx>(&self, v: &'v mut Value<'ctx>) -> Option<&'v mut Value<'ctx>> {
+        match v {
+            Value::Object(map) => map.get_mut(self),
+            _ => None,
+        }
+    }
+
+    fn index_or_insert<'v, 'ctx>(&self, v: &'v mut Value<'ctx>) -> &'v mut Value<'ctx> {
+        if let Value::Null = v {
+            *v = Value::Object(ObjectAsVec::default());
+        }
+        match v {
+            Value::Object(map) => {
+                if let Some(pos) = map.position(self) {
+                    &mut map.0[pos].1
+                } else {
+                    map.push(self.to_owned().into(), Value::Null)
+                }
+            }
+            _ => panic!("cannot access key {:?} in JSON {}", self, Type(v)),
+        }
+    }
+}
+
+impl Index for String {
+    #[inline]
+    fn index_into<'v, 'ctx>(&self, v: &'v Value<'ctx>) -> Option<&'v Value<'ctx>> {
+        self[..].index_into(v)
+    }
+
+    #[inline]
+    fn index_into_mut<'v, 'ctx>(&self, v: &'v mut Value<'ctx>) -> Option<&'v mut Value<'ctx>> {
+        self[..].index_into_mut(v)
+    }
+
+    #[inline]
+    fn index_or_insert<'v, 'ctx>(&self, v: &'v mut Value<'ctx>) -> &'v mut Value<'ctx> {
+        self[..].index_or_insert(v)
+    }
+}
+
+impl<T> Index for &T
+where T: ?Sized + Index
+{
+    #[inline]
+    fn index_into<'v, 'ctx>(&self, v: &'v Value<'ctx>) -> Option<&'v Value<'ctx>> {
+        (**self).index_into(v)
+    }
+
+    #[inline]
+    fn index_into_mut<'v, 'ctx>(&self, v: &'v mut Value<'ctx>) -> Option<&'v mut Value<'ctx>> {
+        (**self).index_into_mut(v)
+    }
+
+    #[inline]
+    fn index_or_insert<'v, 'ctx>(&self, v: &'v mut Value<'ctx>) -> &'v mut Value<'ctx> {
+        (**self).index_or_insert(v)
+    }
+}
+
+// Prevent users from implementing the Index trait. The sealed trait also holds the methods which
+// depend on features, so enabling a feature doesn't add required methods to `Index`.
+mod private {
+    #[cfg(feature = "bumpalo")]
+    use crate::ArenaValue;
+
+    pub trait Sealed {
+        /// Return None if the key is not in the array or object of an [`ArenaValue`].
+        #[cfg(feature = "bumpalo")]
+        fn index_into_arena<'a>(&self, v: &ArenaValue<'a>) -> Option<&'a ArenaValue<'a>>;
+    }
+}
+
+impl private::Sealed for usize {
+    #[cfg(feature = "bumpalo")]
+    #[inline]
+    fn index_into_arena<'a>(&self, v: &ArenaValue<'a>) -> Option<&'a ArenaValue<'a>> {
+        match *v {
+            ArenaValue::Array(values) => values.get(*self),
             _ => None,
         }
     }
 }
+
+impl private::Sealed for str {
+    #[cfg(feature = "bumpalo")]
+    #[inline]
+    fn index_into_arena<'a>(&self, v: &ArenaValue<'a>) -> Option<&'a ArenaValue<'a>> {
+        match *v {
+            ArenaValue::Object(entries) => entries
+                .iter()
+                .find(|(key, _)| *key == self)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+}
+
+impl private::Sealed for String {
+    #[cfg(feature = "bumpalo")]
+    #[inline]
+    fn index_into_arena<'a>(&self, v: &ArenaValue<'a>) -> Option<&'a ArenaValue<'a>> {
+        self[..].index_into_arena(v)
+    }
+}
+
+impl<T> private::Sealed for &T
+where T: ?Sized + private::Sealed
+{
+    #[cfg(feature = "bumpalo")]
+    #[inline]
+    fn index_into_arena<'a>(&self, v: &ArenaValue<'a>) -> Option<&'a ArenaValue<'a>> {
+        (**self).index_into_arena(v)
+    }
+}
+
+/// Used in panic messages.
+struct Type<'a, 'ctx>(&'a Value<'ctx>);
+
+impl std::fmt::Display for Type<'_, '_> {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self.0 {
+            Value::Null => formatter.write_str("null"),
+            Value::Bool(_) => formatter.write_str("boolean"),
+            Value::Number(_) => formatter.write_str("number"),
+            Value::Str(_) => formatter.write_str("string"),
+            Value::Array(_) => formatter.write_str("array"),
+            Value::Object(_) => formatter.write_str("object"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_into_mut() {
+        let mut value: Value = serde_json::from_str(r#"{"a": [1, 2]}"#).unwrap();
+        *"a".index_into_mut(&mut value).unwrap() = Value::Null;
+        assert_eq!(value.get("a"), &Value::Null);
+        assert!(0.index_into_mut(&mut value).is_none());
+        assert!("b".index_into_mut(&mut value).is_none());
+    }
+
+    #[test]
+    fn test_index_or_insert() {
+        let mut value = Value::Null;
+        *"a".index_or_insert(&mut value) = Value::Array(vec![Value::Null]);
+        *0.index_or_insert("a".index_or_insert(&mut value)) = Value::Bool(true);
+        *"b".index_or_insert(&mut value) = Value::Bool(false);
+        *"b".to_string().index_or_insert(&mut value) = Value::Bool(true);
+
+        let expected: Value = serde_json::from_str(r#"{"a": [true], "b": true}"#).unwrap();
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot access index 1 of JSON array of length 1")]
+    fn test_index_or_insert_out_of_bounds() {
+        let mut value = Value::Array(vec![Value::Null]);
+        1.index_or_insert(&mut value);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot access key \"a\" in JSON boolean")]
+    fn test_index_or_insert_wrong_type() {
+        let mut value = Value::Bool(true);
+        "a".index_or_insert(&mut value);
+    }
+}
@@ -48,10 +48,22 @@
 //! as [`OwnedValue`] will take ownership of the `String` and reference slices of
 //! it, rather than making copies.
 //!
+//! A [`SharedValue`] is a reference counted [`OwnedValue`], whose clones are cheap and which can
+//! hand out handles to nested values that keep the document alive.
+//!
+//! To parse many documents in a loop, [`ValueBuffer`] reuses the input buffer and the `Vec`s of
+//! the previous document.
+//!
+//! ## Native parser
+//! [`from_str`] and [`from_slice`] parse JSON directly into a [`Value`] without going through
+//! serde, which is faster than `serde_json::from_str`. They borrow all strings and keys without
+//! escape sequences and accept escaped keys regardless of the `cowkeys` feature flag.
+//!
 //! # Limitations
-//! The feature flag `cowkeys` uses `Cow<str>` instead of `&str` as keys in objects. This enables
-//! support for escaped data in keys. Without the `cowkeys` feature flag `&str` is used, which does
-//! not allow any JSON escaping characters in keys.
+//! The feature flag `cowkeys` enables support for escaped data in keys, which are deserialized
+//! into owned keys. Without the `cowkeys` feature flag keys are always borrowed from the input,
+//! which does not allow any JSON escaping characters in keys. This limitation does not apply to
+//! [`from_str`] and [`from_slice`].
 //!
 //! List of _unsupported_ characters (<https://www.json.org/json-en.html>) in object keys without `cowkeys` feature flag.
 //!
@@ -76,19 +88,42 @@
 //! On a hadoop file system log data set benchmark, I get _714Mb/s_ JSON deserialization throughput
 //! on my machine.
 
+#[cfg(feature = "bumpalo")]
+mod arena;
 mod de;
 mod deserializer;
+mod error;
 mod index;
+mod lines;
 mod num;
+// Some of the original tests of the module use `3.14` and leave a variable unused.
+#[cfg_attr(test, allow(clippy::approx_constant, unused_variables))]
 mod object_vec;
 mod ownedvalue;
+mod parser;
 mod ser;
+mod shared;
+#[cfg(feature = "simd")]
+mod simd;
+mod stream;
 mod value;
 
-#[cfg(feature = "cowkeys")]
 mod cowstr;
 
+#[cfg(feature = "bumpalo")]
+pub use arena::ArenaValue;
+pub use de::DuplicateKeyPolicy;
+pub use error::{Error, LimitExceeded};
+pub use lines::{JsonLines, JsonLinesReader};
 pub use num::Number;
-pub use object_vec::{KeyStrType, ObjectAsVec, ObjectAsVec as Map, ObjectEntry};
-pub use ownedvalue::OwnedValue;
+pub use object_vec::{
+    Entry, KeyStrType, ObjectAsVec, ObjectAsVec as Map, ObjectEntry, OccupiedEntry, VacantEntry,
+};
+pub use ownedvalue::{JsonBuffer, OwnedValue, ValueBuffer};
+pub use parser::{from_slice, from_slice_with_policy, from_str, from_str_with_policy};
+pub use ser::to_value;
+pub use shared::SharedValue;
+#[cfg(feature = "simd")]
+pub use simd::{from_slice_simd, SimdParser};
+pub use stream::ValueStream;
 pub use value::Value;
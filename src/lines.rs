@@ -0,0 +1,373 @@
+use std::io::{self, BufRead, BufReader, Read};
+
+use crate::parser::from_utf8;
+use crate::{DuplicateKeyPolicy, Error, OwnedValue, Value, ValueBuffer};
+
+/// An iterator over the values of a [JSON Lines](https://jsonlines.org/) (also known as NDJSON)
+/// buffer, borrowing from the buffer.
+///
+/// Each line is parsed with [`from_str`](crate::from_str), or with
+/// [`from_str_with_policy`](crate::from_str_with_policy) after [`JsonLines::with_policy`].
+/// Lines consisting only of whitespace are skipped, and lines may end with `\r\n`. A line that
+/// fails to parse yields an error with the line number in the buffer, and iteration continues with
+/// the next line.
+///
+/// ## Example
+/// ```
+/// use serde_json_borrow::{JsonLines, Value};
+///
+/// let data = "{\"id\": 1}\n{\"id\": 2}\n\n{\"id\": }\n";
+/// let mut lines = JsonLines::new(data);
+/// assert_eq!(lines.next().unwrap().unwrap().get("id"), &Value::from(1u64));
+/// assert_eq!(lines.next().unwrap().unwrap().get("id"), &Value::from(2u64));
+/// let err = lines.next().unwrap().unwrap_err();
+/// assert_eq!((err.line(), err.column()), (4, 8));
+/// assert!(lines.next().is_none());
+/// ```
+#[derive(Debug, Clone)]
+pub struct JsonLines<'ctx> {
+    input: &'ctx [u8],
+    pos: usize,
+    line: usize,
+    policy: DuplicateKeyPolicy,
+}
+
+impl<'ctx> JsonLines<'ctx> {
+    /// Creates an iterator over the lines of `input`.
+    pub fn new(input: &'ctx str) -> Self {
+        Self::from_slice(input.as_bytes())
+    }
+
+    /// Creates an iterator over the lines of `input`. Each line is validated for utf-8.
+    pub fn from_slice(input: &'ctx [u8]) -> Self {
+        JsonLines {
+            input,
+            pos: 0,
+            line: 0,
+            policy: DuplicateKeyPolicy::KeepAll,
+        }
+    }
+
+    /// Handles duplicate keys in objects according to `policy`. By default, all entries are
+    /// kept.
+    pub fn with_policy(self, policy: DuplicateKeyPolicy) -> Self {
+        JsonLines { policy, ..self }
+    }
+
+    /// The one-based line number of the last value or error returned, 0 before the first.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+impl<'ctx> Iterator for JsonLines<'ctx> {
+    type Item = Result<Value<'ctx>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pos < self.input.len() {
+            let rest = &self.input[self.pos..];
+            let len = rest.iter().position(|&b| b == b'\n').unwrap_or(rest.len());
+            let line = &rest[..len];
+            self.pos += len + 1;
+            self.line += 1;
+            if is_blank(line) {
+                continue;
+            }
+            let value = crate::from_slice_with_policy(line, self.policy);
+            return Some(value.map_err(|err| err.offset_line(self.line - 1)));
+        }
+        None
+    }
+}
+
+/// An iterator over the values of a [JSON Lines](https://jsonlines.org/) (also known as NDJSON)
+/// stream, yielding an [`OwnedValue`] per line.
+///
+/// Each line is read into a buffer which is reused for the next line. As every `OwnedValue` owns
+/// its data, the line is copied into a new `String`, and its arrays and objects are allocated
+/// anew. [`JsonLinesReader::read_value`] parses into a [`ValueBuffer`] instead, which reuses
+/// these allocations between lines. Lines are validated for utf-8 and parsed with
+/// [`from_str`](crate::from_str), or with [`from_str_with_policy`](crate::from_str_with_policy)
+/// after [`JsonLinesReader::with_policy`]. Lines consisting only of whitespace are skipped. Invalid
+/// lines are returned as [`io::ErrorKind::InvalidData`] errors wrapping an [`Error`] with the line
+/// number in the stream, and iteration continues with the next line. Errors from the reader end
+/// the iteration.
+///
+/// ## Example
+/// ```
+/// use serde_json_borrow::{JsonLinesReader, Value};
+///
+/// let data = "{\"id\": 1}\n{\"id\": 2}\n";
+/// let ids = JsonLinesReader::new(data.as_bytes())
+///     .map(|value| value.unwrap().get("id").as_u64().unwrap())
+///     .collect::<Vec<_>>();
+/// assert_eq!(ids, vec![1, 2]);
+/// ```
+#[derive(Debug)]
+pub struct JsonLinesReader<R> {
+    reader: R,
+    buf: Vec<u8>,
+    line: usize,
+    failed: bool,
+    policy: DuplicateKeyPolicy,
+    values: ValueBuffer,
+}
+
+impl<R: BufRead> JsonLinesReader<R> {
+    /// Creates an iterator over the lines of `reader`.
+    ///
+    /// For readers without an internal buffer, e.g. a `File`, use
+    /// [`JsonLinesReader::from_read`].
+    pub fn new(reader: R) -> Self {
+        JsonLinesReader {
+            reader,
+            buf: Vec::new(),
+            line: 0,
+            failed: false,
+            policy: DuplicateKeyPolicy::KeepAll,
+            values: ValueBuffer::new(),
+        }
+    }
+
+    /// Handles duplicate keys in objects according to `policy`. By default, all entries are
+    /// kept.
+    pub fn with_policy(self, policy: DuplicateKeyPolicy) -> Self {
+        JsonLinesReader {
+            policy,
+            values: self.values.with_policy(policy),
+            ..self
+        }
+    }
+
+    /// Reads the next line and parses it into a [`Value`] borrowing from an internal
+    /// [`ValueBuffer`], which avoids the allocations of an [`OwnedValue`] per line. Returns
+    /// `None` at the end of the stream, and errors like the iterator.
+    ///
+    /// ## Example
+    /// ```
+    /// use serde_json_borrow::JsonLinesReader;
+    ///
+    /// let data = "{\"id\": 1}\n{\"id\": 2}\n";
+    /// let mut lines = JsonLinesReader::new(data.as_bytes());
+    /// let mut sum = 0;
+    /// while let Some(value) = lines.read_value() {
+    ///     sum += value.unwrap().get("id").as_u64().unwrap();
+    /// }
+    /// assert_eq!(sum, 3);
+    /// ```
+    pub fn read_value(&mut self) -> Option<io::Result<&Value<'_>>> {
+        if let Err(err) = self.read_line()? {
+            return Some(Err(err));
+        }
+        let line_number = self.line;
+        let value = self.values.parse_slice(&self.buf);
+        Some(value.map_err(|err| err.offset_line(line_number - 1).into()))
+    }
+
+    /// Reads the next line which is not blank into `buf`.
+    fn read_line(&mut self) -> Option<io::Result<()>> {
+        if self.failed {
+            return None;
+        }
+        loop {
+            self.buf.clear();
+            match self.reader.read_until(b'\n', &mut self.buf) {
+                Ok(0) => return None,
+                Ok(_) => {}
+                Err(err) => {
+                    self.failed = true;
+                    return Some(Err(err));
+                }
+            }
+            self.line += 1;
+            if !is_blank(&self.buf) {
+                return Some(Ok(()));
+            }
+        }
+    }
+
+    /// The one-based line number of the last value or error returned, 0 before the first.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read> JsonLinesReader<BufReader<R>> {
+    /// Creates an iterator over the lines of `reader`, which is wrapped in a [`BufReader`].
+    pub fn from_read(reader: R) -> Self {
+        Self::new(BufReader::new(reader))
+    }
+}
+
+impl<R: BufRead> Iterator for JsonLinesReader<R> {
+    type Item = io::Result<OwnedValue>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Err(err) = self.read_line()? {
+            return Some(Err(err));
+        }
+        let line_number = self.line;
+        let policy = self.policy;
+        let value = from_utf8(&self.buf).and_then(|line| {
+            OwnedValue::parse_with(line.to_string(), |json| {
+                crate::from_str_with_policy(json, policy)
+            })
+        });
+        Some(value.map_err(|err| err.offset_line(line_number - 1).into()))
+    }
+}
+
+/// Whether `line` consists only of JSON whitespace.
+fn is_blank(line: &[u8]) -> bool {
+    line.iter()
+        .all(|b| matches!(b, b' ' | b'\t' | b'\r' | b'\n'))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+
+    #[test]
+    fn test_json_lines() {
+        let data = "{\"a\": 1}\r\n\n  \n[1, 2]\n\"x\"";
+        let values: Vec<Value> = JsonLines::new(data).map(Result::unwrap).collect();
+        assert_eq!(
+            values,
+            vec![
+                crate::from_str("{\"a\": 1}").unwrap(),
+                crate::from_str("[1, 2]").unwrap(),
+                Value::Str("x".into()),
+            ]
+        );
+        assert_eq!(JsonLines::new("").count(), 0);
+        assert_eq!(JsonLines::new("\n\n").count(), 0);
+    }
+
+    #[test]
+    fn test_json_lines_errors() {
+        let data = b"1\n[1,]\n\xff\n2";
+        let mut lines = JsonLines::from_slice(data);
+        assert_eq!(lines.next().unwrap().unwrap(), Value::from(1u64));
+        let err = lines.next().unwrap().unwrap_err();
+        assert_eq!(
+            (err.message(), err.line(), err.column()),
+            ("trailing comma", 2, 4)
+        );
+        assert_eq!(lines.line(), 2);
+        let err = lines.next().unwrap().unwrap_err();
+        assert_eq!(
+            (err.message(), err.line(), err.column()),
+            ("invalid UTF-8", 3, 1)
+        );
+        assert_eq!(lines.next().unwrap().unwrap(), Value::from(2u64));
+        assert_eq!(lines.line(), 4);
+        assert!(lines.next().is_none());
+    }
+
+    #[test]
+    fn test_json_lines_policy() {
+        let data = "{\"a\": 1, \"a\": 2}\n{\"b\": 1}";
+        let mut lines = JsonLines::new(data).with_policy(DuplicateKeyPolicy::Error);
+        let err = lines.next().unwrap().unwrap_err();
+        assert_eq!((err.message(), err.line()), ("duplicate key `a`", 1));
+        assert_eq!(lines.next().unwrap().unwrap().get("b"), &Value::from(1u64));
+
+        let mut lines =
+            JsonLinesReader::new(data.as_bytes()).with_policy(DuplicateKeyPolicy::KeepFirst);
+        let value = lines.next().unwrap().unwrap();
+        assert_eq!(*value, crate::from_str("{\"a\": 1}").unwrap());
+    }
+
+    #[test]
+    fn test_json_lines_reader() {
+        let data = b"{\"a\": \"b\"}\r\n\n[1,]\n\"\xff\"\n\"x\"";
+        let mut lines = JsonLinesReader::new(&data[..]);
+        let value = lines.next().unwrap().unwrap();
+        assert_eq!(value.get("a"), &Value::Str("b".into()));
+        let err = lines.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let err = err.into_inner().unwrap().downcast::<Error>().unwrap();
+        assert_eq!((err.line(), err.column()), (3, 4));
+        let err = lines.next().unwrap().unwrap_err();
+        let err = err.into_inner().unwrap().downcast::<Error>().unwrap();
+        assert_eq!(
+            (err.message(), err.line(), err.column()),
+            ("invalid UTF-8", 4, 2)
+        );
+        assert_eq!(lines.next().unwrap().unwrap().as_str(), Some("x"));
+        assert!(lines.next().is_none());
+        assert_eq!(lines.line(), 5);
+    }
+
+    #[test]
+    fn test_json_lines_form_feed() {
+        // Form feed is ASCII whitespace, but not JSON whitespace.
+        let data = "1\n\x0c\n2";
+        let mut lines = JsonLines::new(data);
+        assert_eq!(lines.next().unwrap().unwrap(), Value::from(1u64));
+        let err = lines.next().unwrap().unwrap_err();
+        assert_eq!((err.line(), err.column()), (2, 1));
+        assert_eq!(lines.next().unwrap().unwrap(), Value::from(2u64));
+
+        let mut lines = JsonLinesReader::new(data.as_bytes());
+        assert!(lines.next().unwrap().is_ok());
+        assert!(lines.next().unwrap().is_err());
+        assert!(lines.next().unwrap().is_ok());
+    }
+
+    #[test]
+    fn test_json_lines_reader_read_value() {
+        let data = b"{\"a\": [1]}\n\n[1,]\n{\"a\": [2, 3]}\n";
+        let mut lines = JsonLinesReader::new(&data[..]);
+        assert_eq!(
+            lines.read_value().unwrap().unwrap().get("a").get(0),
+            &Value::from(1u64)
+        );
+        let err = lines.read_value().unwrap().unwrap_err();
+        let err = err.into_inner().unwrap().downcast::<Error>().unwrap();
+        assert_eq!((err.line(), err.column()), (3, 4));
+        let value = lines.read_value().unwrap().unwrap();
+        assert_eq!(value.get("a").get(1), &Value::from(3u64));
+        assert!(lines.read_value().is_none());
+        assert_eq!(lines.line(), 4);
+    }
+
+    #[test]
+    fn test_json_lines_reader_io_error() {
+        struct Failing;
+        impl Read for Failing {
+            fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::other("boom"))
+            }
+        }
+        let mut lines = JsonLinesReader::new(BufReader::new(Failing));
+        assert_eq!(lines.next().unwrap().unwrap_err().to_string(), "boom");
+        assert!(lines.next().is_none());
+    }
+
+    #[test]
+    fn test_json_lines_reader_from_read() {
+        // Reads a single byte per call and has no internal buffer.
+        struct Bytes<'a>(&'a [u8]);
+        impl Read for Bytes<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                let len = self.0.len().min(buf.len()).min(1);
+                buf[..len].copy_from_slice(&self.0[..len]);
+                self.0 = &self.0[len..];
+                Ok(len)
+            }
+        }
+        let lines = JsonLinesReader::from_read(Bytes(b"{\"id\": 1}\n\n[2]\n"));
+        let values: Vec<_> = lines.map(Result::unwrap).collect();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0].get("id"), &Value::from(1u64));
+        assert_eq!(values[1].get(0), &Value::from(2u64));
+    }
+}
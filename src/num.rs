@@ -1,28 +1,254 @@
+use core::cmp::Ordering;
 use core::hash::{Hash, Hasher};
+use std::fmt;
+#[cfg(not(feature = "arbitrary_precision"))]
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::str::FromStr;
+
+use serde::de;
+
+use crate::parser::Parser;
+use crate::Error;
 
 /// Represents a JSON number, whether integer or floating point.
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
-pub struct Number {
-    pub(crate) n: N,
+///
+/// Integers are stored with up to 128 bits, other numbers as `f64`.
+///
+/// Numbers are ordered by their value. Integers and floats never compare equal, an integer is
+/// ordered before a float of the same value.
+///
+/// With the `arbitrary_precision` feature flag, the parsers of this crate keep the text of
+/// floats and of integers which don't fit into 128 bits, borrowed from the input, next to their
+/// value. Such numbers are serialized with their original digits, and their text is available
+/// via `Number::as_str`. Floats out of the range of `f64` are kept as well, their value is
+/// infinite. [`Number::into_owned`] drops the text.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Number<'ctx> {
+    pub(crate) n: N<'ctx>,
 }
 
-impl From<N> for Number {
-    fn from(n: N) -> Self {
+impl<'ctx> From<N<'ctx>> for Number<'ctx> {
+    fn from(n: N<'ctx>) -> Self {
         Self { n }
     }
 }
 
-#[derive(Copy, Clone)]
-pub(crate) enum N {
+#[derive(Clone, Copy)]
+pub(crate) enum N<'ctx> {
     PosInt(u64),
     /// Always less than zero.
     NegInt(i64),
+    /// Always greater than `u64::MAX`.
+    PosInt128(Int128),
+    /// The bits of an `i128`, always less than `i64::MIN`.
+    NegInt128(Int128),
     /// Always finite.
     Float(f64),
+    /// A float, or `-0`, with its text. The value is infinite if the text is out of the range
+    /// of `f64`.
+    #[cfg_attr(not(feature = "arbitrary_precision"), allow(dead_code))]
+    FloatLexeme(f64, Lexeme<'ctx>),
+    /// An integer which doesn't fit into 128 bits, with its text and its value rounded to the
+    /// nearest `f64`, which may be infinite.
+    #[cfg_attr(not(feature = "arbitrary_precision"), allow(dead_code))]
+    BigInt(f64, Lexeme<'ctx>),
 }
 
-impl Number {
-    /// If the `Number` is an integer, represent it as i64 if possible. Returns
+/// The text of a number in the input, which is valid JSON. Without the `arbitrary_precision`
+/// feature flag, no number is stored as text, and the type is empty to keep `Number` small.
+#[cfg(feature = "arbitrary_precision")]
+#[derive(Clone, Copy)]
+pub(crate) struct Lexeme<'ctx>(&'ctx str);
+
+#[cfg(not(feature = "arbitrary_precision"))]
+#[derive(Clone, Copy)]
+pub(crate) struct Lexeme<'ctx>(PhantomData<&'ctx str>);
+
+impl Deref for Lexeme<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        #[cfg(feature = "arbitrary_precision")]
+        return self.0;
+        #[cfg(not(feature = "arbitrary_precision"))]
+        unreachable!("numbers are only stored as text with the arbitrary_precision feature flag")
+    }
+}
+
+/// A 128-bit integer split into two `u64`, so that it doesn't raise the alignment of `Value` to
+/// 16 bytes.
+#[derive(Clone, Copy)]
+pub(crate) struct Int128 {
+    hi: u64,
+    lo: u64,
+}
+
+impl Int128 {
+    fn new(bits: u128) -> Self {
+        Int128 {
+            hi: (bits >> 64) as u64,
+            lo: bits as u64,
+        }
+    }
+
+    pub(crate) fn get(self) -> u128 {
+        ((self.hi as u128) << 64) | self.lo as u128
+    }
+}
+
+/// A number in the form in which it is ordered.
+enum Canonical<'a> {
+    /// An integer with up to 128 bits, as sign and magnitude. Zero is never negative.
+    Int(bool, u128),
+    Float(f64),
+    /// An integer which doesn't fit into 128 bits.
+    BigInt(&'a str),
+}
+
+impl N<'_> {
+    fn canonical(&self) -> Canonical<'_> {
+        match self {
+            N::PosInt(n) => Canonical::Int(false, *n as u128),
+            N::NegInt(n) => Canonical::Int(true, n.unsigned_abs() as u128),
+            N::PosInt128(n) => Canonical::Int(false, n.get()),
+            N::NegInt128(n) => Canonical::Int(true, (n.get() as i128).unsigned_abs()),
+            N::Float(f) | N::FloatLexeme(f, _) => Canonical::Float(*f),
+            N::BigInt(_, s) => Canonical::BigInt(s),
+        }
+    }
+}
+
+fn cmp_int((a_neg, a): (bool, u128), (b_neg, b): (bool, u128)) -> Ordering {
+    match (a_neg, b_neg) {
+        (false, false) => a.cmp(&b),
+        (true, true) => b.cmp(&a),
+        _ => b_neg.cmp(&a_neg),
+    }
+}
+
+fn cmp_int_float((neg, magnitude): (bool, u128), f: f64) -> Ordering {
+    let approx = if neg {
+        -(magnitude as f64)
+    } else {
+        magnitude as f64
+    };
+    if approx != f {
+        // Rounding to the nearest f64 keeps the order to any other f64.
+        return approx.total_cmp(&f);
+    }
+    // `f` is an integer with at most the magnitude of `u128::MAX` rounded up.
+    if f.abs() >= u128::MAX as f64 {
+        return 0.0f64.total_cmp(&f);
+    }
+    cmp_int((neg, magnitude), (f < 0.0, f.abs() as u128)).then(Ordering::Less)
+}
+
+/// Compares integers written in decimal, without leading zeros.
+fn cmp_big_int(a: &str, b: &str) -> Ordering {
+    let (a_neg, a) = a.strip_prefix('-').map_or((false, a), |a| (true, a));
+    let (b_neg, b) = b.strip_prefix('-').map_or((false, b), |b| (true, b));
+    let magnitude = |a: &str, b: &str| a.len().cmp(&b.len()).then_with(|| a.cmp(b));
+    match (a_neg, b_neg) {
+        (false, false) => magnitude(a, b),
+        (true, true) => magnitude(b, a),
+        _ => b_neg.cmp(&a_neg),
+    }
+}
+
+fn cmp_big_int_float(big: &str, f: f64) -> Ordering {
+    if !f.is_finite() {
+        0.0f64.total_cmp(&f)
+    } else if f.fract() == 0.0 {
+        cmp_big_int(big, &format!("{:.0}", f)).then(Ordering::Less)
+    } else if big.starts_with('-') {
+        // Floats with a fraction are smaller in magnitude than any integer beyond 128 bits.
+        Ordering::Less
+    } else {
+        Ordering::Greater
+    }
+}
+
+impl Ord for N<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.canonical(), other.canonical()) {
+            (Canonical::Int(a_neg, a), Canonical::Int(b_neg, b)) => cmp_int((a_neg, a), (b_neg, b)),
+            (Canonical::Int(neg, n), Canonical::Float(f)) => cmp_int_float((neg, n), f),
+            (Canonical::Float(f), Canonical::Int(neg, n)) => cmp_int_float((neg, n), f).reverse(),
+            (Canonical::Float(a), Canonical::Float(b)) => {
+                if a == b {
+                    Ordering::Equal
+                } else {
+                    a.total_cmp(&b)
+                }
+            }
+            (Canonical::BigInt(a), Canonical::BigInt(b)) => cmp_big_int(a, b),
+            (Canonical::BigInt(a), Canonical::Float(f)) => cmp_big_int_float(a, f),
+            (Canonical::Float(f), Canonical::BigInt(b)) => cmp_big_int_float(b, f).reverse(),
+            (Canonical::BigInt(a), Canonical::Int(..)) => cmp_big_int(a, "0"),
+            (Canonical::Int(..), Canonical::BigInt(b)) => cmp_big_int("0", b),
+        }
+    }
+}
+
+impl PartialOrd for N<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'ctx> Number<'ctx> {
+    /// Creates a number from its text in the input and its value parsed as `f64`. The text has
+    /// to be a valid JSON number, which is a float, `-0`, or an integer which doesn't fit into
+    /// 128 bits.
+    #[cfg(feature = "arbitrary_precision")]
+    pub(crate) fn from_lexeme(lexeme: &'ctx str, value: f64, is_float: bool) -> Self {
+        let n = if is_float {
+            N::FloatLexeme(value, Lexeme(lexeme))
+        } else {
+            N::BigInt(value, Lexeme(lexeme))
+        };
+        Self { n }
+    }
+
+    /// Maps the text of the number with `f`, if it has any. Used to make the number borrow
+    /// from a copy of the input.
+    #[cfg(feature = "arbitrary_precision")]
+    pub(crate) fn map_lexeme(self, f: impl FnOnce(&'ctx str) -> &'ctx str) -> Self {
+        let n = match self.n {
+            N::FloatLexeme(value, Lexeme(s)) => N::FloatLexeme(value, Lexeme(f(s))),
+            N::BigInt(value, Lexeme(s)) => N::BigInt(value, Lexeme(f(s))),
+            n => n,
+        };
+        Self { n }
+    }
+
+    /// Converts a finite `f64` into a `Number`. Returns None for NaN and infinite values, which
+    /// can't be represented in JSON.
+    pub fn from_f64(f: f64) -> Option<Self> {
+        f.is_finite().then_some(Self { n: N::Float(f) })
+    }
+
+    /// Converts the number into a number which is no longer tied to the lifetime of the input.
+    ///
+    /// Numbers stored as text with the `arbitrary_precision` feature flag are converted into
+    /// `f64` and lose their text. Values out of the range of `f64` become `f64::MIN` or
+    /// `f64::MAX`.
+    pub fn into_owned(self) -> Number<'static> {
+        let n = match self.n {
+            N::PosInt(n) => N::PosInt(n),
+            N::NegInt(n) => N::NegInt(n),
+            N::PosInt128(n) => N::PosInt128(n),
+            N::NegInt128(n) => N::NegInt128(n),
+            N::Float(f) => N::Float(f),
+            N::FloatLexeme(f, _) | N::BigInt(f, _) => N::Float(f.clamp(f64::MIN, f64::MAX)),
+        };
+        Number { n }
+    }
+}
+
+impl Number<'_> {
+    /// If the `Number` is an integer, represent it as u64 if possible. Returns
     /// None otherwise.
     pub fn as_u64(&self) -> Option<u64> {
         match self.n {
@@ -30,7 +256,7 @@ impl Number {
             _ => None,
         }
     }
-    /// If the `Number` is an integer, represent it as u64 if possible. Returns
+    /// If the `Number` is an integer, represent it as i64 if possible. Returns
     /// None otherwise.
     pub fn as_i64(&self) -> Option<i64> {
         match self.n {
@@ -51,13 +277,68 @@ impl Number {
         match self.n {
             N::PosInt(n) => Some(n as f64),
             N::NegInt(n) => Some(n as f64),
+            N::PosInt128(n) => Some(n.get() as f64),
+            N::NegInt128(n) => Some(n.get() as i128 as f64),
             N::Float(n) => Some(n),
+            N::FloatLexeme(n, _) | N::BigInt(n, _) => n.is_finite().then_some(n),
         }
     }
 
+    /// If the `Number` is an integer, represent it as u128 if possible. Returns
+    /// None otherwise.
+    pub fn as_u128(&self) -> Option<u128> {
+        match self.n {
+            N::PosInt(n) => Some(n as u128),
+            N::PosInt128(n) => Some(n.get()),
+            _ => None,
+        }
+    }
+
+    /// If the `Number` is an integer, represent it as i128 if possible. Returns
+    /// None otherwise.
+    pub fn as_i128(&self) -> Option<i128> {
+        match self.n {
+            N::PosInt(n) => Some(n as i128),
+            N::NegInt(n) => Some(n as i128),
+            N::PosInt128(n) => i128::try_from(n.get()).ok(),
+            N::NegInt128(n) => Some(n.get() as i128),
+            N::Float(_) | N::FloatLexeme(..) | N::BigInt(..) => None,
+        }
+    }
+
+    /// Returns the text of the number in the input, if it was kept by the parser. See
+    /// [`Number`].
+    #[cfg(feature = "arbitrary_precision")]
+    pub fn as_str(&self) -> Option<&str> {
+        match self.n {
+            N::FloatLexeme(_, Lexeme(s)) | N::BigInt(_, Lexeme(s)) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Represents the number as f64 if this is possible without loss of precision.
+    fn as_f64_exact(&self) -> Option<f64> {
+        match self.n.canonical() {
+            Canonical::Float(f) => f.is_finite().then_some(f),
+            Canonical::Int(neg, magnitude) => {
+                let f = magnitude as f64;
+                let exact = f < u128::MAX as f64 && f as u128 == magnitude;
+                exact.then_some(if neg { -f } else { f })
+            }
+            Canonical::BigInt(s) => {
+                let f = self.as_f64()?;
+                (format!("{:.0}", f) == s).then_some(f)
+            }
+        }
+    }
+
+    fn invalid_value(&self, expected: &str) -> Error {
+        de::Error::invalid_value(self.unexpected(), &expected)
+    }
+
     /// Returns true if the `Number` is a f64.
     pub fn is_f64(&self) -> bool {
-        matches!(self.n, N::Float(_))
+        matches!(self.n, N::Float(_) | N::FloatLexeme(..))
     }
 
     /// Returns true if the `Number` is a u64.
@@ -71,31 +352,36 @@ impl Number {
         match self.n {
             N::PosInt(v) => v <= i64::MAX as u64,
             N::NegInt(_) => true,
-            N::Float(_) => false,
+            N::PosInt128(_)
+            | N::NegInt128(_)
+            | N::Float(_)
+            | N::FloatLexeme(..)
+            | N::BigInt(..) => false,
         }
     }
 }
 
-impl PartialEq for N {
+/// Numbers are compared by value, independent of whether they are stored as text. Floats out of
+/// the range of `f64` are equal to infinity.
+impl PartialEq for N<'_> {
     fn eq(&self, other: &Self) -> bool {
-        match (self, other) {
-            (N::PosInt(a), N::PosInt(b)) => a == b,
-            (N::NegInt(a), N::NegInt(b)) => a == b,
-            (N::Float(a), N::Float(b)) => a == b,
+        match (self.canonical(), other.canonical()) {
+            (Canonical::Int(a_neg, a), Canonical::Int(b_neg, b)) => (a_neg, a) == (b_neg, b),
+            (Canonical::Float(a), Canonical::Float(b)) => a == b,
+            (Canonical::BigInt(a), Canonical::BigInt(b)) => a == b,
             _ => false,
         }
     }
 }
 
-// Implementing Eq is fine since any float values are always finite.
-impl Eq for N {}
+// Implementing Eq is fine since float values are never NaN.
+impl Eq for N<'_> {}
 
-impl Hash for N {
+impl Hash for N<'_> {
     fn hash<H: Hasher>(&self, h: &mut H) {
-        match *self {
-            N::PosInt(i) => i.hash(h),
-            N::NegInt(i) => i.hash(h),
-            N::Float(f) => {
+        match self.canonical() {
+            Canonical::Int(neg, magnitude) => (neg, magnitude).hash(h),
+            Canonical::Float(f) => {
                 if f == 0.0f64 {
                     // There are 2 zero representations, +0 and -0, which
                     // compare equal but have different bits. We use the +0 hash
@@ -105,44 +391,175 @@ impl Hash for N {
                     f.to_bits().hash(h);
                 }
             }
+            Canonical::BigInt(s) => s.hash(h),
         }
     }
 }
 
-impl std::fmt::Display for Number {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.n {
+impl fmt::Debug for Number<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.n {
+            N::PosInt(n) => write!(f, "Number({:?})", n),
+            N::NegInt(n) => write!(f, "Number({:?})", n),
+            N::PosInt128(n) => write!(f, "Number({:?})", n.get()),
+            N::NegInt128(n) => write!(f, "Number({:?})", n.get() as i128),
+            N::Float(n) => write!(f, "Number({:?})", n),
+            N::FloatLexeme(_, s) | N::BigInt(_, s) => write!(f, "Number({})", &**s),
+        }
+    }
+}
+
+impl fmt::Display for Number<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.n {
             N::PosInt(n) => write!(f, "{}", n),
             N::NegInt(n) => write!(f, "{}", n),
+            N::PosInt128(n) => write!(f, "{}", n.get()),
+            N::NegInt128(n) => write!(f, "{}", n.get() as i128),
             N::Float(n) => write!(f, "{}", n),
+            N::FloatLexeme(_, s) | N::BigInt(_, s) => f.write_str(s),
         }
     }
 }
 
-impl From<u64> for Number {
+impl From<u64> for Number<'_> {
     fn from(val: u64) -> Self {
         Self { n: N::PosInt(val) }
     }
 }
 
-impl From<i64> for Number {
+impl From<i64> for Number<'_> {
     fn from(val: i64) -> Self {
-        Self { n: N::NegInt(val) }
+        if val < 0 {
+            Self { n: N::NegInt(val) }
+        } else {
+            Self {
+                n: N::PosInt(val as u64),
+            }
+        }
+    }
+}
+
+impl From<u128> for Number<'_> {
+    fn from(val: u128) -> Self {
+        if let Ok(val) = u64::try_from(val) {
+            Self { n: N::PosInt(val) }
+        } else {
+            Self {
+                n: N::PosInt128(Int128::new(val)),
+            }
+        }
+    }
+}
+
+impl From<i128> for Number<'_> {
+    fn from(val: i128) -> Self {
+        if let Ok(val) = i64::try_from(val) {
+            val.into()
+        } else if val < 0 {
+            Self {
+                n: N::NegInt128(Int128::new(val as u128)),
+            }
+        } else {
+            (val as u128).into()
+        }
     }
 }
 
-impl From<f64> for Number {
+/// Deprecated, use [`Number::from_f64`] instead, which returns `None` for NaN and infinite
+/// values, like `Value::from(f64)` converts them to `Value::Null`. Trait implementations can't
+/// be marked as `#[deprecated]`.
+///
+/// JSON can't represent NaN and infinite values, so this converts NaN to 0 and infinite values
+/// to `f64::MIN` or `f64::MAX`, like `as` casts into integers do, rather than returning an
+/// invalid `Number`.
+impl From<f64> for Number<'_> {
     fn from(val: f64) -> Self {
+        let val = if val.is_nan() {
+            0.0
+        } else {
+            val.clamp(f64::MIN, f64::MAX)
+        };
         Self { n: N::Float(val) }
     }
 }
 
-impl From<Number> for serde_json::value::Number {
-    fn from(num: Number) -> Self {
+/// Parses a JSON number, without surrounding whitespace.
+///
+/// Numbers out of the range of `f64` are rejected, and the text of the number is not kept, see
+/// [`Number::into_owned`].
+///
+/// ```
+/// use serde_json_borrow::Number;
+///
+/// let number: Number = "-1.5e3".parse().unwrap();
+/// assert_eq!(number.as_f64(), Some(-1500.0));
+/// assert!(" 1".parse::<Number>().is_err());
+/// ```
+impl FromStr for Number<'static> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Parser::new(s).parse_number_only().map(Number::into_owned)
+    }
+}
+
+macro_rules! impl_try_from_number {
+    ($($ty:ty)*) => {$(
+        /// Fails if the number is not an integer, or out of range.
+        impl TryFrom<Number<'_>> for $ty {
+            type Error = Error;
+
+            fn try_from(num: Number<'_>) -> Result<Self, Error> {
+                let val = match num.as_i128() {
+                    Some(n) => <$ty>::try_from(n).ok(),
+                    None => num.as_u128().and_then(|n| <$ty>::try_from(n).ok()),
+                };
+                val.ok_or_else(|| num.invalid_value(stringify!($ty)))
+            }
+        }
+    )*};
+}
+
+impl_try_from_number!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize);
+
+/// Fails if the number can't be represented exactly as f64.
+impl TryFrom<Number<'_>> for f64 {
+    type Error = Error;
+
+    fn try_from(num: Number<'_>) -> Result<Self, Error> {
+        num.as_f64_exact().ok_or_else(|| num.invalid_value("f64"))
+    }
+}
+
+/// Fails if the number can't be represented exactly as f32.
+impl TryFrom<Number<'_>> for f32 {
+    type Error = Error;
+
+    fn try_from(num: Number<'_>) -> Result<Self, Error> {
+        num.as_f64_exact()
+            .filter(|&f| f as f32 as f64 == f)
+            .map(|f| f as f32)
+            .ok_or_else(|| num.invalid_value("f32"))
+    }
+}
+
+impl From<Number<'_>> for serde_json::value::Number {
+    fn from(num: Number<'_>) -> Self {
         match num.n {
             N::PosInt(n) => n.into(),
             N::NegInt(n) => n.into(),
-            N::Float(n) => serde_json::value::Number::from_f64(n).unwrap(),
+            // Floats are always finite.
+            N::Float(n) => serde_json::value::Number::from_f64(n).unwrap_or_else(|| 0.into()),
+            // Without its `arbitrary_precision` feature, serde_json stores integers which don't
+            // fit into 64 bits as f64.
+            N::PosInt128(_) | N::NegInt128(_) => {
+                num.to_string().parse().unwrap_or_else(|_| 0.into())
+            }
+            // serde_json rejects text out of the range of f64.
+            N::FloatLexeme(_, s) | N::BigInt(_, s) => {
+                s.parse().unwrap_or_else(|_| num.into_owned().into())
+            }
         }
     }
 }
@@ -151,10 +568,228 @@ impl From<Number> for serde_json::value::Number {
 mod test {
     use super::*;
 
+    /// Parses a number like the parser of a document does.
+    #[cfg(feature = "arbitrary_precision")]
+    fn parse(s: &str) -> Number<'_> {
+        match crate::from_str(s).unwrap() {
+            crate::Value::Number(n) => n,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_from_i64() {
+        assert!(Number::from(5i64) == Number::from(5u64));
+        assert!(Number::from(5i64).is_u64());
+        assert_eq!(Number::from(0i64).as_u64(), Some(0));
+        assert_eq!(Number::from(-5i64).as_u64(), None);
+        assert_eq!(Number::from(-5i64).as_i64(), Some(-5));
+    }
+
     #[test]
     fn test_number_display() {
         assert_eq!(Number::from(42u64).to_string(), "42");
         assert_eq!(Number::from(-42i64).to_string(), "-42");
         assert_eq!(Number::from(3.66).to_string(), "3.66");
     }
+
+    #[test]
+    fn test_ord() {
+        let sorted = [
+            Number::from(f64::MIN),
+            Number::from(-1e40),
+            Number::from(i128::MIN),
+            Number::from(-1.5),
+            Number::from(-1i64),
+            Number::from(-1.0),
+            Number::from(0u64),
+            Number::from(0.0),
+            Number::from(0.5),
+            Number::from(u64::MAX),
+            Number::from(u64::MAX as f64),
+            Number::from(u128::MAX),
+            Number::from(u128::MAX as f64),
+            Number::from(1e40),
+        ];
+        for (i, a) in sorted.iter().enumerate() {
+            for (j, b) in sorted.iter().enumerate() {
+                assert_eq!(a.cmp(b), i.cmp(&j), "{:?} {:?}", a, b);
+            }
+        }
+        assert_eq!(Number::from(-0.0).cmp(&Number::from(0.0)), Ordering::Equal);
+        assert!(Number::from(9007199254740993u64) > Number::from(9007199254740992.0));
+    }
+
+    #[cfg(feature = "arbitrary_precision")]
+    #[test]
+    fn test_ord_big_int() {
+        let big = parse;
+        let sorted = [
+            big("-1e400"),
+            big("-1000000000000000000000000000000000000000"),
+            big("-999999999999999999999999999999999999999"),
+            // -999999999999999939709166371603178586112
+            Number::from(-1e39),
+            Number::from(i128::MIN),
+            Number::from(0.5),
+            Number::from(u128::MAX),
+            big("340282366920938463463374607431768211456"),
+            Number::from(2f64.powi(128)),
+            big("999999999999999999999999999999999999999"),
+            Number::from(f64::MAX),
+            big("1e400"),
+        ];
+        for (i, a) in sorted.iter().enumerate() {
+            for (j, b) in sorted.iter().enumerate() {
+                assert_eq!(a.cmp(b), i.cmp(&j), "{:?} {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_try_from() {
+        assert_eq!(u8::try_from(Number::from(255u64)).unwrap(), 255);
+        let err = u8::try_from(Number::from(256u64)).unwrap_err();
+        assert_eq!(err.to_string(), "invalid value: integer `256`, expected u8");
+        assert_eq!(i8::try_from(Number::from(-128i64)).unwrap(), -128);
+        assert!(u32::try_from(Number::from(-1i64)).is_err());
+        assert!(i64::try_from(Number::from(1.0)).is_err());
+        assert_eq!(i128::try_from(Number::from(i128::MIN)).unwrap(), i128::MIN);
+        assert_eq!(u128::try_from(Number::from(u128::MAX)).unwrap(), u128::MAX);
+        assert!(usize::try_from(Number::from(u128::MAX)).is_err());
+
+        assert_eq!(f64::try_from(Number::from(1.5)).unwrap(), 1.5);
+        assert_eq!(
+            f64::try_from(Number::from(1u64 << 53)).unwrap(),
+            9007199254740992.0
+        );
+        assert!(f64::try_from(Number::from((1u64 << 53) + 1)).is_err());
+        assert!(f64::try_from(Number::from(u128::MAX)).is_err());
+        assert_eq!(f32::try_from(Number::from(0.5)).unwrap(), 0.5);
+        assert!(f32::try_from(Number::from(0.1)).is_err());
+        assert!(f32::try_from(Number::from(f64::MAX)).is_err());
+        assert_eq!(f32::try_from(Number::from(1u64 << 24)).unwrap(), 16777216.0);
+        assert!(f32::try_from(Number::from((1u64 << 24) + 1)).is_err());
+    }
+
+    #[test]
+    fn test_copy() {
+        fn assert_copy<T: Copy>() {}
+        assert_copy::<Number>();
+    }
+
+    #[test]
+    fn test_from_f64() {
+        assert_eq!(Number::from_f64(1.5), Some(Number::from(1.5)));
+        assert_eq!(Number::from_f64(f64::NAN), None);
+        assert_eq!(Number::from_f64(f64::INFINITY), None);
+        assert_eq!(Number::from(f64::NAN), Number::from(0.0));
+        assert_eq!(Number::from(f64::INFINITY), Number::from(f64::MAX));
+        assert_eq!(Number::from(f64::NEG_INFINITY), Number::from(f64::MIN));
+        assert_eq!(
+            serde_json::Number::from(Number::from(f64::NAN)),
+            serde_json::Number::from_f64(0.0).unwrap()
+        );
+        assert_eq!(
+            serde_json::Value::from(crate::Value::from(f64::INFINITY)),
+            serde_json::Value::Null
+        );
+        assert_eq!(crate::Value::from(f64::NAN), crate::Value::Null);
+        assert_eq!(
+            crate::to_value(&f32::NEG_INFINITY).unwrap(),
+            crate::Value::Null
+        );
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("42".parse::<Number>().unwrap(), Number::from(42u64));
+        assert_eq!("-0".parse::<Number>().unwrap(), Number::from(-0.0));
+        assert_eq!(
+            "-170141183460469231731687303715884105728"
+                .parse::<Number>()
+                .unwrap(),
+            Number::from(i128::MIN)
+        );
+        assert_eq!("2.5e-1".parse::<Number>().unwrap(), Number::from(0.25));
+        for invalid in ["", "01", "1.", "+1", "1 ", "NaN", "1e400", "[1]"] {
+            assert!(invalid.parse::<Number>().is_err(), "{}", invalid);
+        }
+        let err = "1x".parse::<Number>().unwrap_err();
+        assert_eq!((err.message(), err.column()), ("trailing characters", 2));
+    }
+
+    #[test]
+    fn test_int128() {
+        let pos = Number::from(u128::MAX);
+        assert_eq!(pos.as_u128(), Some(u128::MAX));
+        assert_eq!(pos.as_i128(), None);
+        assert_eq!(pos.as_u64(), None);
+        assert!(!pos.is_u64() && !pos.is_i64() && !pos.is_f64());
+        assert_eq!(pos.to_string(), u128::MAX.to_string());
+        let neg = Number::from(i128::MIN);
+        assert_eq!(neg.as_i128(), Some(i128::MIN));
+        assert_eq!(neg.as_u128(), None);
+        assert_eq!(neg.as_f64(), Some(i128::MIN as f64));
+        assert_eq!(neg.to_string(), i128::MIN.to_string());
+
+        assert_eq!(Number::from(5u128), Number::from(5u64));
+        assert_eq!(Number::from(-5i128), Number::from(-5i64));
+        assert_eq!(Number::from(i128::MAX), Number::from(i128::MAX as u128));
+        assert_eq!(Number::from(-5i128).as_i128(), Some(-5));
+        assert_eq!(Number::from(5u64).as_u128(), Some(5));
+        assert_eq!(
+            serde_json::Number::from(pos),
+            serde_json::Number::from_f64(u128::MAX as f64).unwrap()
+        );
+    }
+
+    #[cfg(feature = "arbitrary_precision")]
+    #[test]
+    fn test_lexeme() {
+        let int = parse("340282366920938463463374607431768211455");
+        assert_eq!(int, Number::from(u128::MAX));
+        assert_eq!(int.as_str(), None);
+        let big = parse("340282366920938463463374607431768211456");
+        assert!(!big.is_u64() && !big.is_i64() && !big.is_f64());
+        assert_eq!(big.as_u128(), None);
+        assert_eq!(big.as_f64(), Some(3.402823669209385e38));
+        assert_eq!(
+            big.as_str(),
+            Some("340282366920938463463374607431768211456")
+        );
+        assert_eq!(big.to_string(), "340282366920938463463374607431768211456");
+        let huge = parse("-1701411834604692317316873037158841057280");
+        assert_eq!(huge.as_i128(), None);
+        assert_eq!(huge.as_f64(), Some(-1.7014118346046923e39));
+
+        let float = parse("1.10");
+        assert!(float.is_f64());
+        assert_eq!(float.as_f64(), Some(1.1));
+        assert_eq!(float.to_string(), "1.10");
+        assert_eq!(float, Number::from(1.1));
+        assert_eq!(parse("-0"), Number::from(0.0));
+        assert_eq!(parse("-0").as_str(), Some("-0"));
+        assert_ne!(parse("1e0"), Number::from(1u64));
+
+        let out_of_range = parse("1e400");
+        assert!(out_of_range.is_f64());
+        assert_eq!(out_of_range.as_f64(), None);
+        assert_eq!(out_of_range.to_string(), "1e400");
+        assert_eq!(out_of_range, parse("2e400"));
+        assert!(f64::try_from(out_of_range).is_err());
+
+        let owned = parse(&String::from("2.50")).into_owned();
+        assert_eq!(owned.as_str(), None);
+        assert_eq!(owned, Number::from(2.5));
+        assert_eq!(out_of_range.into_owned(), Number::from(f64::MAX));
+        assert_eq!(
+            serde_json::Number::from(parse("2.50")),
+            serde_json::Number::from_f64(2.5).unwrap()
+        );
+        assert_eq!(
+            serde_json::Number::from(parse("-1e400")),
+            serde_json::Number::from_f64(f64::MIN).unwrap()
+        );
+    }
 }
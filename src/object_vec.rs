@@ -2,18 +2,21 @@
 #![allow(clippy::useless_asref)]
 
 use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+#[cfg(not(feature = "key_index"))]
+use std::marker::PhantomData;
+use std::ops::{self, Bound, RangeBounds};
 
 use crate::Value;
 
-#[cfg(feature = "cowkeys")]
-/// The string type used. Can be toggled between `&str` and `Cow<str>` via `cowstr` feature flag
+/// The string type used for keys. A wrapper around `Cow<str>`, keys are borrowed when possible.
+///
+/// Whether escaped keys are accepted during deserialization can be toggled via the `cowkeys`
+/// feature flag.
 pub type KeyStrType<'a> = crate::cowstr::CowStr<'a>;
 
-#[cfg(not(feature = "cowkeys"))]
-/// The string type used. Can be toggled between `&str` and `Cow<str>` via `cowstr` feature flag
-/// Cow strings
-pub type KeyStrType<'a> = &'a str;
-
 /// Represents a JSON key/value type.
 ///
 /// For performance reasons we use a Vec instead of a Hashmap.
@@ -22,36 +25,122 @@ pub type KeyStrType<'a> = &'a str;
 /// The ObjectAsVec struct is a wrapper around a Vec of (&str, Value) pairs.
 /// It provides methods to make it easy to migrate from serde_json::Value::Object or
 /// serde_json::Map.
-#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
-pub struct ObjectAsVec<'ctx>(pub(crate) Vec<(KeyStrType<'ctx>, Value<'ctx>)>);
+///
+/// With the `key_index` feature flag, a hash index over the keys can be built for large objects
+/// with `ObjectAsVec::build_index`, which makes key lookups constant time while keeping the
+/// insertion order.
+#[derive(Default, Clone)]
+pub struct ObjectAsVec<'ctx>(
+    pub(crate) Vec<(KeyStrType<'ctx>, Value<'ctx>)>,
+    pub(crate) KeyIndexSlot<'ctx>,
+);
 
-#[cfg(feature = "cowkeys")]
-impl<'ctx> From<Vec<(&'ctx str, Value<'ctx>)>> for ObjectAsVec<'ctx> {
-    fn from(vec: Vec<(&'ctx str, Value<'ctx>)>) -> Self {
-        Self::from_iter(vec)
+/// Maps each key to the position of its first occurrence in the Vec.
+type KeyIndex<'ctx> = HashMap<KeyStrType<'ctx>, usize>;
+
+/// Holds the hash index of an object, if one was built.
+///
+/// Without the `key_index` feature flag this is zero sized, so objects are not larger than the
+/// Vec.
+#[derive(Default, Clone)]
+pub(crate) struct KeyIndexSlot<'ctx>(
+    #[cfg(feature = "key_index")] Option<Box<KeyIndex<'ctx>>>,
+    #[cfg(not(feature = "key_index"))] PhantomData<KeyIndex<'ctx>>,
+);
+
+impl<'ctx> KeyIndexSlot<'ctx> {
+    #[inline]
+    fn get(&self) -> Option<&KeyIndex<'ctx>> {
+        #[cfg(feature = "key_index")]
+        return self.0.as_deref();
+        #[cfg(not(feature = "key_index"))]
+        return None;
+    }
+
+    #[inline]
+    fn get_mut(&mut self) -> Option<&mut KeyIndex<'ctx>> {
+        #[cfg(feature = "key_index")]
+        return self.0.as_deref_mut();
+        #[cfg(not(feature = "key_index"))]
+        return None;
+    }
+
+    /// Builds an index over the keys of the entries.
+    #[cfg(feature = "key_index")]
+    fn build(entries: &[(KeyStrType<'ctx>, Value<'ctx>)]) -> Self {
+        let mut index = KeyIndex::with_capacity(entries.len());
+        fill_index(&mut index, entries.iter().map(|(k, _)| k));
+        Self(Some(Box::new(index)))
+    }
+
+    /// Returns the slot for a copy of the object with the given entries, which holds an index
+    /// only if this slot does.
+    #[cfg_attr(not(feature = "key_index"), allow(unused_variables))]
+    pub(crate) fn rebuild_for<'a>(
+        &self,
+        entries: &[(KeyStrType<'a>, Value<'a>)],
+    ) -> KeyIndexSlot<'a> {
+        #[cfg(feature = "key_index")]
+        if self.0.is_some() {
+            return KeyIndexSlot::build(entries);
+        }
+        KeyIndexSlot::default()
+    }
+}
+
+/// Adds the keys to the index, which maps each key to its first position.
+fn fill_index<'a, 'ctx: 'a>(
+    index: &mut KeyIndex<'ctx>,
+    keys: impl Iterator<Item = &'a KeyStrType<'ctx>>,
+) {
+    for (pos, k) in keys.enumerate() {
+        index.entry(k.clone()).or_insert(pos);
+    }
+}
+
+impl fmt::Debug for ObjectAsVec<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ObjectAsVec").field(&self.0).finish()
+    }
+}
+
+impl PartialEq for ObjectAsVec<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for ObjectAsVec<'_> {}
+
+impl Hash for ObjectAsVec<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
     }
 }
 
-#[cfg(not(feature = "cowkeys"))]
 impl<'ctx> From<Vec<(&'ctx str, Value<'ctx>)>> for ObjectAsVec<'ctx> {
     fn from(vec: Vec<(&'ctx str, Value<'ctx>)>) -> Self {
-        Self(vec)
+        Self::from_iter(vec)
     }
 }
 
 impl<'ctx> FromIterator<(&'ctx str, Value<'ctx>)> for ObjectAsVec<'ctx> {
     fn from_iter<T: IntoIterator<Item = (&'ctx str, Value<'ctx>)>>(iter: T) -> Self {
-        Self(iter.into_iter().map(|(k, v)| (k.into(), v)).collect())
+        Self::from_entries(iter.into_iter().map(|(k, v)| (k.into(), v)).collect())
     }
 }
 
 impl<'ctx> ObjectAsVec<'ctx> {
+    /// Creates an object from the given entries, without an index.
+    #[inline]
+    pub(crate) fn from_entries(entries: Vec<(KeyStrType<'ctx>, Value<'ctx>)>) -> Self {
+        Self(entries, KeyIndexSlot::default())
+    }
+
     /// Access to the underlying Vec.
     ///
     /// # Note
-    /// Since KeyStrType can be changed via a feature flag avoid using `as_vec` and use other
-    /// methods instead. This could be a problem with feature unification, when one crate uses it
-    /// as `&str` and another uses it as `Cow<str>`, both will get `Cow<str>`
+    /// Prefer the other methods, which give access to the keys as `&str`.
     #[inline]
     pub fn as_vec(&self) -> &Vec<(KeyStrType<'ctx>, Value<'ctx>)> {
         &self.0
@@ -63,16 +152,82 @@ impl<'ctx> ObjectAsVec<'ctx> {
         self.0.into_iter().map(|el| (el.0.into(), el.1)).collect()
     }
 
+    /// Converts the object into an object that owns all its keys and values, and is no longer
+    /// tied to the lifetime of the input.
+    ///
+    /// See [`Value::into_owned`].
+    pub fn into_owned(self) -> ObjectAsVec<'static> {
+        let entries: Vec<_> = self
+            .0
+            .into_iter()
+            .map(|(k, v)| (k.into_owned(), v.into_owned()))
+            .collect();
+        let index = self.1.rebuild_for(&entries);
+        ObjectAsVec(entries, index)
+    }
+
+    /// Copies the object into an object that owns all its keys and values.
+    ///
+    /// See [`Value::to_owned_value`].
+    pub fn to_owned_object(&self) -> ObjectAsVec<'static> {
+        let entries: Vec<_> = self
+            .0
+            .iter()
+            .map(|(k, v)| (k.to_string().into(), v.to_owned_value()))
+            .collect();
+        let index = self.1.rebuild_for(&entries);
+        ObjectAsVec(entries, index)
+    }
+
+    /// Builds a hash index over the keys, which speeds up all lookups by key from linear to
+    /// constant time.
+    ///
+    /// Objects are not indexed by default, since building the index costs more than a few linear
+    /// scans for typical small objects. Once built, the index is kept up to date by all
+    /// methods which modify the object. Inserting entries updates the index incrementally,
+    /// removing entries rebuilds it.
+    ///
+    /// With duplicate keys, lookups return the first matching entry, like without an index.
+    ///
+    /// Requires the `key_index` feature flag.
+    ///
+    /// ## Example
+    /// ```
+    /// # use serde_json_borrow::{ObjectAsVec, Value};
+    /// let mut obj = ObjectAsVec::default();
+    /// obj.build_index();
+    /// for i in 0..1000u64 {
+    ///     obj.entry(format!("key{i}")).or_insert(Value::from(i));
+    /// }
+    /// assert_eq!(obj.get("key999"), Some(&Value::from(999u64)));
+    /// ```
+    #[cfg(feature = "key_index")]
+    pub fn build_index(&mut self) {
+        self.1 = KeyIndexSlot::build(&self.0);
+    }
+
+    /// Removes the hash index built by [`ObjectAsVec::build_index`], if any.
+    #[cfg(feature = "key_index")]
+    pub fn drop_index(&mut self) {
+        self.1 = KeyIndexSlot(None);
+    }
+
+    /// Returns true if a hash index was built with [`ObjectAsVec::build_index`].
+    #[cfg(feature = "key_index")]
+    #[inline]
+    pub fn has_index(&self) -> bool {
+        self.1.get().is_some()
+    }
+
     /// Returns a reference to the value corresponding to the key.
     ///
     /// ## Performance
     /// As this is backed by a Vec, this searches linearly through the Vec as may be much more
-    /// expensive than a `Hashmap` for larger Objects.
+    /// expensive than a `Hashmap` for larger Objects, unless an index was built with
+    /// `ObjectAsVec::build_index`.
     #[inline]
     pub fn get(&self, key: &str) -> Option<&Value<'ctx>> {
-        self.0
-            .iter()
-            .find_map(|(k, v)| if *k == key { Some(v) } else { None })
+        self.position(key).map(|pos| &self.0[pos].1)
     }
 
     /// Returns a mutable reference to the value corresponding to the key, if it exists.
@@ -82,9 +237,7 @@ impl<'ctx> ObjectAsVec<'ctx> {
     /// expensive than a `Hashmap` for larger Objects.
     #[inline]
     pub fn get_mut(&mut self, key: &str) -> Option<&mut Value<'ctx>> {
-        self.0
-            .iter_mut()
-            .find_map(|(k, v)| if *k == key { Some(v) } else { None })
+        self.position(key).map(|pos| &mut self.0[pos].1)
     }
 
     /// Returns the key-value pair corresponding to the supplied key.
@@ -94,13 +247,8 @@ impl<'ctx> ObjectAsVec<'ctx> {
     /// expensive than a `Hashmap` for larger Objects.
     #[inline]
     pub fn get_key_value(&self, key: &str) -> Option<(&str, &Value<'ctx>)> {
-        self.0.iter().find_map(|(k, v)| {
-            if *k == key {
-                Some((k.as_ref(), v))
-            } else {
-                None
-            }
-        })
+        self.position(key)
+            .and_then(|pos| self.get_key_value_at(pos))
     }
 
     /// Finds an [`ObjectEntry`] in the Map by key.
@@ -128,15 +276,12 @@ impl<'ctx> ObjectAsVec<'ctx> {
     /// ```
     #[inline]
     pub fn get_entry(&self, key: &str) -> Option<ObjectEntry<'_, 'ctx>> {
-        self.0.iter().enumerate().find_map(|(index, (k, v))| {
-            if *k == key {
-                Some(ObjectEntry {
-                    index,
-                    key: k.as_ref(),
-                    value: v,
-                })
-            } else {
-                None
+        self.position(key).map(|index| {
+            let (k, v) = &self.0[index];
+            ObjectEntry {
+                index,
+                key: k.as_ref(),
+                value: v,
             }
         })
     }
@@ -200,7 +345,7 @@ impl<'ctx> ObjectAsVec<'ctx> {
     /// expensive than a `Hashmap` for larger Objects.
     #[inline]
     pub fn contains_key(&self, key: &str) -> bool {
-        self.0.iter().any(|(k, _)| *k == key)
+        self.position(key).is_some()
     }
 
     /// Inserts a key-value pair into the object.
@@ -213,13 +358,11 @@ impl<'ctx> ObjectAsVec<'ctx> {
     /// through all elements to find a matching key.
     #[inline]
     pub fn insert(&mut self, key: &'ctx str, value: Value<'ctx>) -> Option<Value<'ctx>> {
-        for (k, v) in &mut self.0 {
-            if *k == key {
-                return Some(std::mem::replace(v, value));
-            }
+        if let Some(pos) = self.position(key) {
+            return Some(std::mem::replace(&mut self.0[pos].1, value));
         }
         // If the key is not found, push the new key-value pair to the end of the Vec
-        self.0.push((key.into(), value));
+        self.push(key.into(), value);
         None
     }
 
@@ -232,11 +375,10 @@ impl<'ctx> ObjectAsVec<'ctx> {
     #[inline]
     pub fn insert_or_get_mut(&mut self, key: &'ctx str, value: Value<'ctx>) -> &mut Value<'ctx> {
         // get position to circumvent lifetime issue
-        if let Some(pos) = self.0.iter_mut().position(|(k, _)| *k == key) {
+        if let Some(pos) = self.position(key) {
             &mut self.0[pos].1
         } else {
-            self.0.push((key.into(), value));
-            &mut self.0.last_mut().unwrap().1
+            self.push(key.into(), value)
         }
     }
 
@@ -256,9 +398,293 @@ impl<'ctx> ObjectAsVec<'ctx> {
         key: &'ctx str,
         value: Value<'ctx>,
     ) -> &mut Value<'ctx> {
-        self.0.push((key.into(), value));
-        let idx = self.0.len() - 1;
-        &mut self.0[idx].1
+        self.push(key.into(), value)
+    }
+
+    /// Gets the given key's corresponding entry in the object for in-place manipulation.
+    ///
+    /// The key can be a borrowed `&str` or an owned `String`.
+    ///
+    /// ## Example
+    /// ```
+    /// # use serde_json_borrow::{ObjectAsVec, Value};
+    /// let mut counters = ObjectAsVec::default();
+    /// for word in ["a", "b", "a"] {
+    ///     let counter = counters.entry(word).or_insert(Value::from(0u64));
+    ///     *counter = Value::from(counter.as_u64().unwrap() + 1);
+    /// }
+    /// assert_eq!(counters.get("a"), Some(&Value::from(2u64)));
+    /// assert_eq!(counters.get("b"), Some(&Value::from(1u64)));
+    /// ```
+    ///
+    /// ## Performance
+    /// This operation is linear in the size of the Vec because it potentially requires iterating
+    /// through all elements to find a matching key. The returned entry gives access to the value
+    /// without searching again.
+    #[inline]
+    pub fn entry<K>(&mut self, key: K) -> Entry<'_, 'ctx>
+    where K: Into<KeyStrType<'ctx>> {
+        let key = key.into();
+        match self.position(&key) {
+            Some(index) => Entry::Occupied(OccupiedEntry { index, map: self }),
+            None => Entry::Vacant(VacantEntry { key, map: self }),
+        }
+    }
+
+    /// Removes a key from the object, returning the value at the key if the key was previously
+    /// in the object.
+    ///
+    /// This is an alias for [`ObjectAsVec::shift_remove`], which preserves the order of the
+    /// remaining entries. Use [`ObjectAsVec::swap_remove`] if the order doesn't matter.
+    ///
+    /// ## Performance
+    /// This operation is linear in the size of the Vec.
+    #[inline]
+    pub fn remove(&mut self, key: &str) -> Option<Value<'ctx>> {
+        self.shift_remove(key)
+    }
+
+    /// Removes a key from the object, returning the stored key and value if the key was
+    /// previously in the object.
+    ///
+    /// Like [`ObjectAsVec::remove`], this preserves the order of the remaining entries.
+    #[inline]
+    pub fn remove_entry(&mut self, key: &str) -> Option<(Cow<'ctx, str>, Value<'ctx>)> {
+        self.shift_remove_entry(key)
+    }
+
+    /// Removes a key from the object by swapping it with the last entry, returning the value at
+    /// the key if the key was previously in the object.
+    ///
+    /// If the key is contained multiple times, only the first entry is removed.
+    ///
+    /// ## Performance
+    /// This operation is linear in the size of the Vec because it potentially requires iterating
+    /// through all elements to find a matching key. The removal itself is O(1).
+    #[inline]
+    pub fn swap_remove(&mut self, key: &str) -> Option<Value<'ctx>> {
+        self.swap_remove_entry(key).map(|(_, v)| v)
+    }
+
+    /// Removes a key from the object by swapping it with the last entry, returning the stored
+    /// key and value if the key was previously in the object.
+    #[inline]
+    pub fn swap_remove_entry(&mut self, key: &str) -> Option<(Cow<'ctx, str>, Value<'ctx>)> {
+        let index = self.position(key)?;
+        self.swap_remove_index(index)
+    }
+
+    /// Removes the entry at `index` by swapping it with the last entry. Returns `None` if
+    /// `index` is out of bounds.
+    ///
+    /// ## Performance
+    /// This operation is O(1). Only if the object has an index and contains duplicate keys, the
+    /// index is rebuilt, which is linear in the size of the Vec.
+    #[inline]
+    pub fn swap_remove_index(&mut self, index: usize) -> Option<(Cow<'ctx, str>, Value<'ctx>)> {
+        if index < self.0.len() {
+            let (k, v) = self.0.swap_remove(index);
+            self.unindex_swap_removed(index, &k);
+            Some((k.into(), v))
+        } else {
+            None
+        }
+    }
+
+    /// Removes a key from the object by shifting all following entries, returning the value at
+    /// the key if the key was previously in the object.
+    ///
+    /// The order of the remaining entries is preserved. If the key is contained multiple times,
+    /// only the first entry is removed.
+    ///
+    /// ## Performance
+    /// This operation is linear in the size of the Vec.
+    #[inline]
+    pub fn shift_remove(&mut self, key: &str) -> Option<Value<'ctx>> {
+        self.shift_remove_entry(key).map(|(_, v)| v)
+    }
+
+    /// Removes a key from the object by shifting all following entries, returning the stored
+    /// key and value if the key was previously in the object.
+    #[inline]
+    pub fn shift_remove_entry(&mut self, key: &str) -> Option<(Cow<'ctx, str>, Value<'ctx>)> {
+        let index = self.position(key)?;
+        self.shift_remove_index(index)
+    }
+
+    /// Removes the entry at `index` by shifting all following entries. Returns `None` if `index`
+    /// is out of bounds.
+    ///
+    /// ## Performance
+    /// This operation is linear in the number of entries after `index`.
+    #[inline]
+    pub fn shift_remove_index(&mut self, index: usize) -> Option<(Cow<'ctx, str>, Value<'ctx>)> {
+        if index < self.0.len() {
+            let (k, v) = self.0.remove(index);
+            self.unindex_shift_removed(index, &k);
+            Some((k.into(), v))
+        } else {
+            None
+        }
+    }
+
+    /// Retains only the entries specified by the predicate, preserving their order.
+    ///
+    /// In other words, removes all entries for which `keep(&key, &mut value)` returns `false`.
+    ///
+    /// ## Example
+    /// ```
+    /// # use serde_json_borrow::{ObjectAsVec, Value};
+    /// let mut obj = ObjectAsVec::from(vec![
+    ///     ("user", Value::Str("John".into())),
+    ///     ("password", Value::Str("secret".into())),
+    /// ]);
+    /// obj.retain(|key, _value| key != "password");
+    /// assert_eq!(obj.keys().collect::<Vec<_>>(), vec!["user"]);
+    /// ```
+    #[inline]
+    pub fn retain<F>(&mut self, mut keep: F)
+    where F: FnMut(&str, &mut Value<'ctx>) -> bool {
+        self.0.retain_mut(|(k, v)| keep(k, v));
+        self.reindex();
+    }
+
+    /// Removes the entries in `range` from the object and returns them as an iterator, with the
+    /// keys normalized to Cow.
+    ///
+    /// The order of the remaining entries is preserved.
+    ///
+    /// # Panics
+    /// Panics if the starting point is greater than the end point or if the end point is greater
+    /// than the length of the object.
+    #[inline]
+    pub fn drain<R>(
+        &mut self,
+        range: R,
+    ) -> impl Iterator<Item = (Cow<'ctx, str>, Value<'ctx>)> + '_
+    where
+        R: RangeBounds<usize>,
+    {
+        if let Some(index) = self.1.get_mut() {
+            let start = match range.start_bound() {
+                Bound::Included(&start) => start,
+                Bound::Excluded(&start) => start.saturating_add(1),
+                Bound::Unbounded => 0,
+            };
+            let end = match range.end_bound() {
+                Bound::Included(&end) => end.saturating_add(1),
+                Bound::Excluded(&end) => end,
+                Bound::Unbounded => self.0.len(),
+            };
+            // The index is updated before draining, since the returned iterator borrows the
+            // object. Invalid ranges are left to `Vec::drain` to panic on.
+            if start <= end && end <= self.0.len() {
+                index.clear();
+                let kept = self.0[..start].iter().chain(&self.0[end..]);
+                fill_index(index, kept.map(|(k, _)| k));
+            }
+        }
+        self.0.drain(range).map(|(k, v)| (k.into(), v))
+    }
+
+    /// Removes the last entry and returns it, or `None` if the object is empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<(Cow<'ctx, str>, Value<'ctx>)> {
+        let (k, v) = self.0.pop()?;
+        if let Some(index) = self.1.get_mut() {
+            // If the index points to the popped entry, it was the only entry with this key.
+            if index.get(&*k) == Some(&self.0.len()) {
+                index.remove(&*k);
+            }
+        }
+        Some((k.into(), v))
+    }
+
+    /// Shortens the object, keeping the first `len` entries and dropping the rest.
+    ///
+    /// If `len` is greater than the object's current length, this has no effect.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        if let Some(index) = self.1.get_mut() {
+            index.retain(|_, pos| *pos < len);
+        }
+        self.0.truncate(len);
+    }
+
+    /// Removes all entries from the object, keeping the allocated memory for reuse.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.0.clear();
+        if let Some(index) = self.1.get_mut() {
+            index.clear();
+        }
+    }
+
+    /// Returns the index of the first entry with the key.
+    #[inline]
+    pub(crate) fn position(&self, key: &str) -> Option<usize> {
+        match self.1.get() {
+            Some(index) => index.get(key).copied(),
+            None => self.0.iter().position(|(k, _)| *k == key),
+        }
+    }
+
+    /// Appends an entry without checking for an existing key, and returns a mutable reference to
+    /// its value.
+    #[inline]
+    pub(crate) fn push(&mut self, key: KeyStrType<'ctx>, value: Value<'ctx>) -> &mut Value<'ctx> {
+        if let Some(index) = self.1.get_mut() {
+            index.entry(key.clone()).or_insert(self.0.len());
+        }
+        self.0.push((key, value));
+        &mut self.0.last_mut().unwrap().1
+    }
+
+    /// Updates the index after the entry with `key` at `pos` was removed by swapping it with the
+    /// last entry, if the object is indexed.
+    #[inline]
+    fn unindex_swap_removed(&mut self, pos: usize, key: &str) {
+        let Some(index) = self.1.get_mut() else {
+            return;
+        };
+        if index.len() != self.0.len() + 1 {
+            // The object contains duplicate keys, whose first positions may have changed.
+            return self.reindex();
+        }
+        index.remove(key);
+        if let Some((moved, _)) = self.0.get(pos) {
+            if let Some(moved_pos) = index.get_mut(&**moved) {
+                *moved_pos = pos;
+            }
+        }
+    }
+
+    /// Updates the index after the entry with `key` at `pos` was removed by shifting all
+    /// following entries, if the object is indexed.
+    #[inline]
+    fn unindex_shift_removed(&mut self, pos: usize, key: &str) {
+        let Some(index) = self.1.get_mut() else {
+            return;
+        };
+        if index.len() != self.0.len() + 1 {
+            // The object contains duplicate keys, whose first positions may have changed.
+            return self.reindex();
+        }
+        index.remove(key);
+        for (k, _) in &self.0[pos..] {
+            if let Some(shifted_pos) = index.get_mut(&**k) {
+                *shifted_pos -= 1;
+            }
+        }
+    }
+
+    /// Rebuilds the index after entries were removed, if the object is indexed.
+    #[inline]
+    fn reindex(&mut self) {
+        if let Some(index) = self.1.get_mut() {
+            index.clear();
+            fill_index(index, self.0.iter().map(|(k, _)| k));
+        }
     }
 }
 
@@ -277,6 +703,200 @@ pub struct ObjectEntry<'a, 'ctx> {
     pub value: &'a Value<'ctx>,
 }
 
+/// A view into a single entry in an object, which may either be vacant or occupied.
+///
+/// This enum is constructed from the [`ObjectAsVec::entry`] method.
+pub enum Entry<'a, 'ctx> {
+    /// A vacant entry.
+    Vacant(VacantEntry<'a, 'ctx>),
+    /// An occupied entry.
+    Occupied(OccupiedEntry<'a, 'ctx>),
+}
+
+/// A view into a vacant entry in an [`ObjectAsVec`]. It is part of the [`Entry`] enum.
+pub struct VacantEntry<'a, 'ctx> {
+    key: KeyStrType<'ctx>,
+    map: &'a mut ObjectAsVec<'ctx>,
+}
+
+/// A view into an occupied entry in an [`ObjectAsVec`]. It is part of the [`Entry`] enum.
+pub struct OccupiedEntry<'a, 'ctx> {
+    index: usize,
+    map: &'a mut ObjectAsVec<'ctx>,
+}
+
+impl<'a, 'ctx> Entry<'a, 'ctx> {
+    /// Returns a reference to this entry's key.
+    pub fn key(&self) -> &str {
+        match self {
+            Entry::Vacant(e) => e.key(),
+            Entry::Occupied(e) => e.key(),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the default if empty, and returns a mutable
+    /// reference to the value in the entry.
+    pub fn or_insert(self, default: Value<'ctx>) -> &'a mut Value<'ctx> {
+        match self {
+            Entry::Vacant(entry) => entry.insert(default),
+            Entry::Occupied(entry) => entry.into_mut(),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the result of the default function if empty,
+    /// and returns a mutable reference to the value in the entry.
+    pub fn or_insert_with<F>(self, default: F) -> &'a mut Value<'ctx>
+    where F: FnOnce() -> Value<'ctx> {
+        match self {
+            Entry::Vacant(entry) => entry.insert(default()),
+            Entry::Occupied(entry) => entry.into_mut(),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the result of the default function if empty,
+    /// and returns a mutable reference to the value in the entry.
+    ///
+    /// The default function is called with the key of the entry.
+    pub fn or_insert_with_key<F>(self, default: F) -> &'a mut Value<'ctx>
+    where F: FnOnce(&str) -> Value<'ctx> {
+        match self {
+            Entry::Vacant(entry) => {
+                let value = default(entry.key());
+                entry.insert(value)
+            }
+            Entry::Occupied(entry) => entry.into_mut(),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting `Value::Null` if empty, and returns a mutable
+    /// reference to the value in the entry.
+    pub fn or_default(self) -> &'a mut Value<'ctx> {
+        self.or_insert_with(Value::default)
+    }
+
+    /// Provides in-place mutable access to an occupied entry before any potential inserts into
+    /// the object.
+    pub fn and_modify<F>(self, f: F) -> Self
+    where F: FnOnce(&mut Value<'ctx>) {
+        match self {
+            Entry::Occupied(mut entry) => {
+                f(entry.get_mut());
+                Entry::Occupied(entry)
+            }
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        }
+    }
+}
+
+impl<'a, 'ctx> VacantEntry<'a, 'ctx> {
+    /// Gets a reference to the key that would be used when inserting a value through the
+    /// VacantEntry.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Takes ownership of the key.
+    pub fn into_key(self) -> Cow<'ctx, str> {
+        self.key.into()
+    }
+
+    /// Sets the value of the entry with the VacantEntry's key, and returns a mutable reference
+    /// to it.
+    ///
+    /// The entry is appended to the end of the object.
+    pub fn insert(self, value: Value<'ctx>) -> &'a mut Value<'ctx> {
+        self.map.push(self.key, value)
+    }
+}
+
+impl<'a, 'ctx> OccupiedEntry<'a, 'ctx> {
+    /// Gets a reference to the key in the entry.
+    pub fn key(&self) -> &str {
+        &self.map.0[self.index].0
+    }
+
+    /// Returns the position of the entry in the underlying Vec.
+    ///
+    /// The index can be used with [`ObjectAsVec::get_key_value_at`].
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Gets a reference to the value in the entry.
+    pub fn get(&self) -> &Value<'ctx> {
+        &self.map.0[self.index].1
+    }
+
+    /// Gets a mutable reference to the value in the entry.
+    pub fn get_mut(&mut self) -> &mut Value<'ctx> {
+        &mut self.map.0[self.index].1
+    }
+
+    /// Converts the entry into a mutable reference to its value, with the lifetime of the
+    /// object.
+    pub fn into_mut(self) -> &'a mut Value<'ctx> {
+        &mut self.map.0[self.index].1
+    }
+
+    /// Sets the value of the entry, and returns the entry's old value.
+    pub fn insert(&mut self, value: Value<'ctx>) -> Value<'ctx> {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    /// Takes the value of the entry out of the object, and returns it.
+    ///
+    /// Like [`ObjectAsVec::swap_remove`], this swaps the entry with the last entry and does not
+    /// preserve the order. Use [`OccupiedEntry::shift_remove`] to preserve the order.
+    pub fn remove(self) -> Value<'ctx> {
+        self.remove_entry().1
+    }
+
+    /// Takes the key and value of the entry out of the object, and returns them.
+    ///
+    /// Like [`OccupiedEntry::remove`], this does not preserve the order.
+    pub fn remove_entry(self) -> (Cow<'ctx, str>, Value<'ctx>) {
+        self.map.swap_remove_index(self.index).unwrap()
+    }
+
+    /// Takes the value of the entry out of the object by shifting all following entries, and
+    /// returns it.
+    pub fn shift_remove(self) -> Value<'ctx> {
+        self.map.shift_remove_index(self.index).unwrap().1
+    }
+}
+
+/// Access an element of this object. Panics if the given key is not present in the object.
+///
+/// ```
+/// # use serde_json_borrow::{ObjectAsVec, Value};
+/// #
+/// let obj = ObjectAsVec::from(vec![("type", Value::Str("person".into()))]);
+/// assert_eq!(obj["type"], Value::Str("person".into()));
+/// ```
+impl<'ctx> ops::Index<&str> for ObjectAsVec<'ctx> {
+    type Output = Value<'ctx>;
+
+    fn index(&self, key: &str) -> &Value<'ctx> {
+        self.get(key).expect("no entry found for key")
+    }
+}
+
+/// Mutably access an element of this object. Panics if the given key is not present in the
+/// object.
+///
+/// ```
+/// # use serde_json_borrow::{ObjectAsVec, Value};
+/// #
+/// let mut obj = ObjectAsVec::from(vec![("key", Value::Null)]);
+/// obj["key"] = Value::Bool(true);
+/// assert_eq!(obj["key"], Value::Bool(true));
+/// ```
+impl ops::IndexMut<&str> for ObjectAsVec<'_> {
+    fn index_mut(&mut self, key: &str) -> &mut Self::Output {
+        self.get_mut(key).expect("no entry found for key")
+    }
+}
+
 impl<'ctx> From<ObjectAsVec<'ctx>> for serde_json::Map<String, serde_json::Value> {
     fn from(val: ObjectAsVec<'ctx>) -> Self {
         val.iter()
@@ -519,4 +1139,316 @@ mod tests {
         let (key, value) = obj.get_key_value_at(idx).unwrap();
         assert_eq!(key, "city");
     }
+
+    #[test]
+    fn test_index_ops() {
+        let mut obj = ObjectAsVec::from(vec![("a", Value::Null), ("b", Value::Bool(true))]);
+        assert_eq!(obj["b"], Value::Bool(true));
+        obj["a"] = Value::Bool(false);
+        assert_eq!(obj.get("a"), Some(&Value::Bool(false)));
+    }
+
+    #[test]
+    #[should_panic(expected = "no entry found for key")]
+    fn test_index_missing_key() {
+        let obj = ObjectAsVec::from(vec![("a", Value::Null)]);
+        let _ = &obj["b"];
+    }
+
+    #[test]
+    fn test_into_owned() {
+        let data = r#"{"a": {"b": "c"}}"#.to_string();
+        let value: Value = serde_json::from_str(&data).unwrap();
+        let obj = value.as_object().unwrap().clone();
+        let copy = obj.to_owned_object();
+        let owned = obj.into_owned();
+        drop(value);
+        drop(data);
+        assert_eq!(owned, copy);
+        assert!(owned
+            .as_vec()
+            .iter()
+            .all(|(k, _)| matches!(k.0, Cow::Owned(_))));
+        assert_eq!(owned.get("a").unwrap().get("b"), &Value::Str("c".into()));
+    }
+
+    /// Builds an object from its entries, like the tuple struct constructor before the key
+    /// index was added.
+    #[allow(non_snake_case)]
+    fn ObjectAsVec<'ctx>(entries: Vec<(KeyStrType<'ctx>, Value<'ctx>)>) -> ObjectAsVec<'ctx> {
+        ObjectAsVec::from_entries(entries)
+    }
+
+    fn abcd() -> ObjectAsVec<'static> {
+        ObjectAsVec::from(vec![
+            ("a", Value::Number(0u64.into())),
+            ("b", Value::Number(1u64.into())),
+            ("c", Value::Number(2u64.into())),
+            ("d", Value::Number(3u64.into())),
+        ])
+    }
+
+    #[test]
+    fn test_swap_remove() {
+        let mut obj = abcd();
+        assert_eq!(obj.swap_remove("b"), Some(Value::Number(1u64.into())));
+        assert_eq!(obj.keys().collect::<Vec<_>>(), vec!["a", "d", "c"]);
+        assert_eq!(obj.swap_remove("b"), None);
+        assert_eq!(
+            obj.swap_remove_entry("a"),
+            Some(("a".into(), Value::Number(0u64.into())))
+        );
+        assert_eq!(obj.keys().collect::<Vec<_>>(), vec!["c", "d"]);
+        assert_eq!(obj.swap_remove_index(0).unwrap().0, "c");
+        assert_eq!(obj.swap_remove_index(1), None);
+        assert_eq!(obj.len(), 1);
+    }
+
+    #[test]
+    fn test_shift_remove() {
+        let mut obj = abcd();
+        assert_eq!(obj.shift_remove("b"), Some(Value::Number(1u64.into())));
+        assert_eq!(obj.keys().collect::<Vec<_>>(), vec!["a", "c", "d"]);
+        assert_eq!(obj.shift_remove("b"), None);
+        assert_eq!(
+            obj.shift_remove_entry("a"),
+            Some(("a".into(), Value::Number(0u64.into())))
+        );
+        assert_eq!(obj.shift_remove_index(0).unwrap().0, "c");
+        assert_eq!(obj.shift_remove_index(1), None);
+        assert_eq!(obj.keys().collect::<Vec<_>>(), vec!["d"]);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut obj = abcd();
+        assert_eq!(obj.remove("b"), Some(Value::Number(1u64.into())));
+        assert_eq!(obj.keys().collect::<Vec<_>>(), vec!["a", "c", "d"]);
+        assert_eq!(obj.remove("b"), None);
+        assert_eq!(obj.remove_entry("a").unwrap().0, "a");
+        assert_eq!(obj.keys().collect::<Vec<_>>(), vec!["c", "d"]);
+    }
+
+    #[test]
+    fn test_remove_duplicate_key() {
+        let mut obj = ObjectAsVec::from(vec![("a", Value::Bool(true)), ("a", Value::Bool(false))]);
+        assert_eq!(obj.shift_remove("a"), Some(Value::Bool(true)));
+        assert_eq!(obj.get("a"), Some(&Value::Bool(false)));
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut obj = abcd();
+        obj.retain(|key, value| {
+            *value = Value::Null;
+            key != "b" && key != "c"
+        });
+        assert_eq!(
+            obj.iter().collect::<Vec<_>>(),
+            vec![("a", &Value::Null), ("d", &Value::Null)]
+        );
+    }
+
+    #[test]
+    fn test_drain() {
+        let mut obj = abcd();
+        let drained: Vec<_> = obj.drain(1..3).map(|(k, _)| k).collect();
+        assert_eq!(drained, vec!["b", "c"]);
+        assert_eq!(obj.keys().collect::<Vec<_>>(), vec!["a", "d"]);
+        assert_eq!(obj.drain(..).count(), 2);
+        assert!(obj.is_empty());
+    }
+
+    #[test]
+    fn test_pop_truncate_clear() {
+        let mut obj = abcd();
+        assert_eq!(obj.pop(), Some(("d".into(), Value::Number(3u64.into()))));
+        obj.truncate(5);
+        assert_eq!(obj.len(), 3);
+        obj.truncate(1);
+        assert_eq!(obj.keys().collect::<Vec<_>>(), vec!["a"]);
+        obj.clear();
+        assert!(obj.is_empty());
+        assert_eq!(obj.pop(), None);
+    }
+
+    #[test]
+    fn test_entry_vacant() {
+        let mut obj = abcd();
+        match obj.entry("e") {
+            Entry::Vacant(entry) => {
+                assert_eq!(entry.key(), "e");
+                *entry.insert(Value::Null) = Value::Bool(true);
+            }
+            Entry::Occupied(_) => panic!("expected vacant entry"),
+        }
+        assert_eq!(obj.get_key_value_at(4), Some(("e", &Value::Bool(true))));
+
+        let key = String::from("f");
+        match obj.entry(key) {
+            Entry::Vacant(entry) => assert_eq!(entry.into_key(), "f"),
+            Entry::Occupied(_) => panic!("expected vacant entry"),
+        }
+        assert_eq!(obj.len(), 5);
+    }
+
+    #[test]
+    fn test_entry_occupied() {
+        let mut obj = abcd();
+        match obj.entry("c") {
+            Entry::Occupied(mut entry) => {
+                assert_eq!(entry.key(), "c");
+                assert_eq!(entry.index(), 2);
+                assert_eq!(entry.get(), &Value::Number(2u64.into()));
+                assert_eq!(entry.insert(Value::Null), Value::Number(2u64.into()));
+                assert_eq!(entry.into_mut(), &Value::Null);
+            }
+            Entry::Vacant(_) => panic!("expected occupied entry"),
+        }
+        match obj.entry("a") {
+            Entry::Occupied(entry) => assert_eq!(entry.remove(), Value::Number(0u64.into())),
+            Entry::Vacant(_) => panic!("expected occupied entry"),
+        }
+        assert_eq!(obj.keys().collect::<Vec<_>>(), vec!["d", "b", "c"]);
+        match obj.entry("d") {
+            Entry::Occupied(entry) => {
+                assert_eq!(entry.shift_remove(), Value::Number(3u64.into()))
+            }
+            Entry::Vacant(_) => panic!("expected occupied entry"),
+        }
+        assert_eq!(obj.keys().collect::<Vec<_>>(), vec!["b", "c"]);
+        match obj.entry("b") {
+            Entry::Occupied(entry) => assert_eq!(entry.remove_entry().0, "b"),
+            Entry::Vacant(_) => panic!("expected occupied entry"),
+        }
+        assert_eq!(obj.keys().collect::<Vec<_>>(), vec!["c"]);
+    }
+
+    #[test]
+    fn test_entry_or_insert() {
+        let mut obj = abcd();
+        assert_eq!(
+            obj.entry("a").or_insert(Value::Null),
+            &Value::Number(0u64.into())
+        );
+        assert_eq!(
+            obj.entry("x").or_insert(Value::Bool(true)),
+            &Value::Bool(true)
+        );
+        assert_eq!(
+            obj.entry("y").or_insert_with(|| Value::Bool(false)),
+            &Value::Bool(false)
+        );
+        assert_eq!(
+            obj.entry("z")
+                .or_insert_with_key(|key| Value::Str(key.to_owned().into())),
+            &Value::Str("z".into())
+        );
+        assert_eq!(obj.entry("w").or_default(), &Value::Null);
+        assert_eq!(obj.entry("w").key(), "w");
+        assert_eq!(obj.len(), 8);
+    }
+
+    #[test]
+    fn test_entry_and_modify() {
+        let mut obj = abcd();
+        let increment = |value: &mut Value| *value = Value::from(value.as_u64().unwrap() + 1);
+        obj.entry("a")
+            .and_modify(increment)
+            .or_insert(Value::from(0u64));
+        obj.entry("new")
+            .and_modify(increment)
+            .or_insert(Value::from(0u64));
+        assert_eq!(obj.get("a"), Some(&Value::from(1u64)));
+        assert_eq!(obj.get("new"), Some(&Value::from(0u64)));
+    }
+
+    #[test]
+    #[cfg(feature = "key_index")]
+    fn test_index_lookup() {
+        let mut obj = abcd();
+        obj.0.push(("a".into(), Value::Null));
+        assert!(!obj.has_index());
+        obj.build_index();
+        assert!(obj.has_index());
+        assert_eq!(obj.get("a"), Some(&Value::Number(0u64.into())));
+        assert_eq!(obj.get_entry("c").unwrap().index, 2);
+        assert!(obj.contains_key("d"));
+        assert!(!obj.contains_key("e"));
+
+        assert_eq!(obj.insert("e", Value::Bool(true)), None);
+        assert_eq!(obj.insert("e", Value::Bool(false)), Some(Value::Bool(true)));
+        obj.entry(String::from("f")).or_default();
+        assert_eq!(obj.get_entry("f").unwrap().index, 6);
+        obj.drop_index();
+        assert!(!obj.has_index());
+        assert_eq!(obj.get_entry("f").unwrap().index, 6);
+    }
+
+    #[test]
+    #[cfg(feature = "key_index")]
+    fn test_index_removal() {
+        let mut obj = abcd();
+        obj.build_index();
+        assert_eq!(obj.swap_remove("a"), Some(Value::Number(0u64.into())));
+        assert_eq!(obj.get_entry("d").unwrap().index, 0);
+        assert_eq!(obj.shift_remove("d"), Some(Value::Number(3u64.into())));
+        assert_eq!(obj.get_entry("c").unwrap().index, 1);
+        assert_eq!(obj.pop().unwrap().0, "c");
+        assert_eq!(obj.get("c"), None);
+        obj.retain(|_, _| false);
+        assert_eq!(obj.get("b"), None);
+
+        let mut obj = abcd();
+        obj.build_index();
+        assert_eq!(obj.drain(1..3).count(), 2);
+        assert_eq!(obj.get_entry("d").unwrap().index, 1);
+        assert_eq!(obj.get("b"), None);
+        obj.truncate(1);
+        assert_eq!(obj.get("d"), None);
+        assert_eq!(obj.get("a"), Some(&Value::Number(0u64.into())));
+        obj.clear();
+        assert_eq!(obj.get("a"), None);
+        obj.insert("a", Value::Null);
+        assert_eq!(obj.get("a"), Some(&Value::Null));
+    }
+
+    #[test]
+    #[cfg(feature = "key_index")]
+    fn test_index_removal_duplicate_keys() {
+        let mut obj = ObjectAsVec::from(vec![
+            ("a", Value::Number(0u64.into())),
+            ("b", Value::Number(1u64.into())),
+            ("a", Value::Number(2u64.into())),
+            ("c", Value::Number(3u64.into())),
+        ]);
+        obj.build_index();
+        assert_eq!(obj.swap_remove("a"), Some(Value::Number(0u64.into())));
+        assert_eq!(obj.get_entry("c").unwrap().index, 0);
+        assert_eq!(obj.get_entry("a").unwrap().index, 2);
+        assert_eq!(obj.shift_remove("c"), Some(Value::Number(3u64.into())));
+        assert_eq!(obj.get_entry("b").unwrap().index, 0);
+        assert_eq!(obj.get_entry("a").unwrap().index, 1);
+        assert_eq!(obj.swap_remove("a"), Some(Value::Number(2u64.into())));
+        assert_eq!(obj.get("a"), None);
+    }
+
+    #[test]
+    #[cfg(feature = "key_index")]
+    fn test_index_ignored_by_eq() {
+        let mut obj = abcd();
+        obj.build_index();
+        assert_eq!(obj, abcd());
+        assert_eq!(format!("{:?}", obj), format!("{:?}", abcd()));
+        assert!(obj.clone().into_owned().has_index());
+    }
+
+    #[test]
+    #[cfg(not(feature = "key_index"))]
+    fn test_size() {
+        assert_eq!(
+            size_of::<ObjectAsVec>(),
+            size_of::<Vec<(KeyStrType, Value)>>()
+        );
+    }
 }
@@ -1,7 +1,12 @@
-use std::io;
+use std::borrow::Cow;
+use std::fmt;
+use std::io::{self, Read};
 use std::ops::Deref;
+use std::sync::Arc;
 
-use crate::Value;
+use crate::object_vec::ObjectAsVec;
+use crate::parser::{from_utf8, Parser, VecPool};
+use crate::{DuplicateKeyPolicy, Error, LimitExceeded, Value};
 
 /// Parses a `String` into `Value`, by taking ownership of `String` and reference slices from it.
 ///
@@ -9,9 +14,14 @@ use crate::Value;
 /// passed `str`. This means that the `Value` can only be used as long as the original `str` is
 /// valid. With [`OwnedValue`], you get a owned Value instead.
 ///
-/// Note: `OwnedValue` does not implement `Deserialize`, as it is not intended to be used for
-/// deserialization. It is designed to be used when you already have a `String` containing JSON
-/// data, and you want to parse it into a `Value` without worrying about lifetimes.
+/// Besides `String`, the JSON can be kept in any [`JsonBuffer`], e.g. an `Arc<str>` or, with the
+/// `bytes` feature flag, a `bytes::Bytes`, see [`OwnedValue::from_buffer`]. Cloning an
+/// `OwnedValue` shares the buffer if cloning the buffer does, like for `Arc<str>` and `Bytes`.
+/// Otherwise the buffer is copied and the clone borrows from the copy.
+///
+/// `OwnedValue` implements `Deserialize`, so it can be used as the type of a field that keeps
+/// the JSON of a subtree, when deserializing with `serde_json`. The JSON text of the subtree is
+/// captured via `serde_json::value::RawValue` and parsed into the `OwnedValue`.
 ///
 /// ## Example
 /// ```
@@ -23,13 +33,122 @@ use crate::Value;
 /// assert_eq!(owned_value.get("age"), &Value::Number(30_u64.into()));
 /// ```
 ///
-#[derive(Clone, Debug, Eq, PartialEq, Hash)]
-pub struct OwnedValue {
+#[derive(Debug, Eq, PartialEq, Hash)]
+pub struct OwnedValue<B = String> {
     /// Keep owned data, to be able to safely reference it from Value<'static>
-    _data: String,
+    data: B,
     value: Value<'static>,
 }
 
+/// An owned buffer containing JSON, from which an [`OwnedValue`] can borrow.
+///
+/// Implemented for `String`, `Box<str>`, `Arc<str>`, `Vec<u8>` and, with the `bytes` feature
+/// flag, `bytes::Bytes`. It can be implemented for other owners, e.g. memory mapped files.
+///
+/// # Safety
+/// The returned bytes must stay valid and unchanged as long as the buffer is alive, also when
+/// the buffer is moved. If the buffer implements `Clone`, clones must contain the same bytes.
+pub unsafe trait JsonBuffer {
+    /// Returns the contents of the buffer.
+    fn as_bytes(&self) -> &[u8];
+
+    /// Returns the contents of the buffer as `str`, if they are known to be utf-8, which skips
+    /// validation.
+    fn as_str(&self) -> Option<&str> {
+        None
+    }
+}
+
+unsafe impl JsonBuffer for String {
+    fn as_bytes(&self) -> &[u8] {
+        self.as_bytes()
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+unsafe impl JsonBuffer for Box<str> {
+    fn as_bytes(&self) -> &[u8] {
+        str::as_bytes(self)
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+unsafe impl JsonBuffer for Arc<str> {
+    fn as_bytes(&self) -> &[u8] {
+        str::as_bytes(self)
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+unsafe impl JsonBuffer for Vec<u8> {
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+#[cfg(feature = "bytes")]
+unsafe impl JsonBuffer for bytes::Bytes {
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+/// Returns the contents of `buffer` as `str`, validating them for utf-8 if necessary.
+fn buffer_str<B: JsonBuffer>(buffer: &B) -> Result<&str, Error> {
+    match buffer.as_str() {
+        Some(json) => Ok(json),
+        None => from_utf8(buffer.as_bytes()),
+    }
+}
+
+impl<B: JsonBuffer> OwnedValue<B> {
+    /// Takes ownership of `buffer` and parses it into a [crate::Value] with
+    /// [`from_str`](crate::from_str), borrowing from the buffer without copying it.
+    ///
+    /// Buffers which are not known to contain a `str` are validated for utf-8.
+    ///
+    /// ## Example
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use serde_json_borrow::{OwnedValue, Value};
+    /// let raw_json: Arc<str> = r#"{"name": "John"}"#.into();
+    /// let owned_value = OwnedValue::from_buffer(raw_json).unwrap();
+    /// let clone = owned_value.clone();
+    /// assert_eq!(clone.get("name"), &Value::Str("John".into()));
+    /// assert!(Arc::ptr_eq(owned_value.buffer(), clone.buffer()));
+    /// ```
+    pub fn from_buffer(buffer: B) -> io::Result<Self> {
+        let value = crate::from_str(buffer_str(&buffer)?)?;
+        let value = unsafe { extend_lifetime(value) };
+        Ok(Self {
+            data: buffer,
+            value,
+        })
+    }
+}
+
+impl<B> OwnedValue<B> {
+    /// Returns the buffer the value borrows from.
+    pub fn buffer(&self) -> &B {
+        &self.data
+    }
+
+    /// Returns the `Value` reference.
+    pub fn get_value(&self) -> &Value<'_> {
+        &self.value
+    }
+}
+
 impl OwnedValue {
     /// Validates `&[u8]` for utf-8 and parses it into a [crate::Value].
     pub fn from_slice(data: &[u8]) -> io::Result<Self> {
@@ -38,6 +157,51 @@ impl OwnedValue {
         Self::from_string(data)
     }
 
+    /// Validates `Vec<u8>` for utf-8 and parses it into a [crate::Value].
+    ///
+    /// Takes ownership of the passed bytes without copying them.
+    pub fn from_vec(data: Vec<u8>) -> io::Result<Self> {
+        let data = String::from_utf8(data)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Invalid UTF-8"))?;
+        Self::from_string(data)
+    }
+
+    /// Reads all data from `reader`, validates it for utf-8 and parses it into a
+    /// [crate::Value].
+    ///
+    /// The data is read into the buffer of the `OwnedValue`, without copying it. For untrusted
+    /// input use [`OwnedValue::from_reader_with_limit`].
+    pub fn from_reader<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        Self::from_vec(data)
+    }
+
+    /// Reads all data from `reader` like [`OwnedValue::from_reader`], but fails with a
+    /// [`LimitExceeded`] error if there are more than `max_len` bytes.
+    ///
+    /// At most `max_len + 1` bytes are read from `reader`.
+    ///
+    /// ## Example
+    /// ```
+    /// use serde_json_borrow::{OwnedValue, Value};
+    ///
+    /// let body = br#"{"name": "John"}"#;
+    /// let owned_value = OwnedValue::from_reader_with_limit(&body[..], 1024).unwrap();
+    /// assert_eq!(owned_value.get("name"), &Value::Str("John".into()));
+    /// assert!(OwnedValue::from_reader_with_limit(&body[..], 8).is_err());
+    /// ```
+    pub fn from_reader_with_limit<R: Read>(reader: R, max_len: usize) -> io::Result<Self> {
+        let mut data = Vec::new();
+        reader
+            .take((max_len as u64).saturating_add(1))
+            .read_to_end(&mut data)?;
+        if data.len() > max_len {
+            return Err(LimitExceeded::new(max_len).into());
+        }
+        Self::from_vec(data)
+    }
+
     /// Takes serialized JSON `&str` and parses it into a [crate::Value].
     ///
     /// Clones the passed str.
@@ -47,28 +211,108 @@ impl OwnedValue {
         Self::from_string(json_str)
     }
 
-    /// Takes serialized JSON `String` and parses it into a [crate::Value].
+    /// Takes serialized JSON `String` and parses it into a [crate::Value] with
+    /// [`from_str`](crate::from_str).
     pub fn from_string(json_str: String) -> io::Result<Self> {
-        let value: Value = serde_json::from_str(&json_str)?;
+        Ok(Self::parse_with(json_str, crate::from_str)?)
+    }
+
+    /// Takes serialized JSON `String` and parses it into a [crate::Value].
+    pub fn parse_from(json_str: String) -> io::Result<Self> {
+        Self::from_string(json_str)
+    }
+
+    /// Validates `&[u8]` for utf-8 and parses it into a [crate::Value], handling duplicate keys
+    /// in objects according to `policy`.
+    ///
+    /// See [`OwnedValue::from_string_with_policy`].
+    pub fn from_slice_with_policy(data: &[u8], policy: DuplicateKeyPolicy) -> io::Result<Self> {
+        let data = String::from_utf8(data.to_vec())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Invalid UTF-8"))?;
+        Self::from_string_with_policy(data, policy)
+    }
+
+    /// Takes serialized JSON `&str` and parses it into a [crate::Value], handling duplicate keys
+    /// in objects according to `policy`.
+    ///
+    /// Clones the passed str. See [`OwnedValue::from_string_with_policy`].
+    pub fn from_str_with_policy(json_str: &str, policy: DuplicateKeyPolicy) -> io::Result<Self> {
+        Self::from_string_with_policy(json_str.to_string(), policy)
+    }
+
+    /// Takes serialized JSON `String` and parses it into a [crate::Value] with
+    /// [`from_str_with_policy`](crate::from_str_with_policy), handling duplicate keys in objects
+    /// according to `policy`.
+    ///
+    /// ## Example
+    /// ```
+    /// use serde_json_borrow::{DuplicateKeyPolicy, OwnedValue};
+    /// let raw_json = r#"{"name": "John", "name": "Jane"}"#.to_string();
+    /// let result = OwnedValue::from_string_with_policy(raw_json, DuplicateKeyPolicy::Error);
+    /// assert!(result.is_err());
+    /// ```
+    pub fn from_string_with_policy(
+        json_str: String,
+        policy: DuplicateKeyPolicy,
+    ) -> io::Result<Self> {
+        Ok(Self::parse_with(json_str, |json_str| {
+            crate::from_str_with_policy(json_str, policy)
+        })?)
+    }
+
+    /// Takes ownership of `json_str` and parses it with `parse`, which can only borrow from the
+    /// passed `&str`.
+    pub(crate) fn parse_with<E>(
+        json_str: String,
+        parse: impl for<'a> FnOnce(&'a str) -> Result<Value<'a>, E>,
+    ) -> Result<Self, E> {
+        let value = parse(&json_str)?;
         let value = unsafe { extend_lifetime(value) };
         Ok(Self {
-            _data: json_str,
+            data: json_str,
             value,
         })
     }
 
-    /// Takes serialized JSON `String` and parses it into a [crate::Value].
-    pub fn parse_from(json_str: String) -> io::Result<Self> {
-        Self::from_string(json_str)
+    /// Wraps a `Value<'static>`, e.g. created via [`Value::into_owned`], without copying it.
+    ///
+    /// ## Example
+    /// ```
+    /// use serde_json_borrow::{OwnedValue, Value};
+    /// let json = r#"{"name": "John"}"#.to_string();
+    /// let value: Value = serde_json::from_str(&json).unwrap();
+    /// let owned_value = OwnedValue::from_value(value.into_owned());
+    /// drop(json);
+    /// assert_eq!(owned_value.get("name"), &Value::Str("John".into()));
+    /// ```
+    pub fn from_value(value: Value<'static>) -> Self {
+        Self {
+            data: String::new(),
+            value,
+        }
     }
+}
 
-    /// Returns the `Value` reference.
-    pub fn get_value(&self) -> &Value<'_> {
-        &self.value
+impl<B: JsonBuffer + Clone> Clone for OwnedValue<B> {
+    fn clone(&self) -> Self {
+        let data = self.data.clone();
+        if data.as_bytes().as_ptr() == self.data.as_bytes().as_ptr() {
+            return Self {
+                data,
+                value: self.value.clone(),
+            };
+        }
+        // The buffer was copied, so the strings borrowed from the original buffer need to
+        // borrow from the copy instead.
+        let old = buffer_str(&self.data).expect("buffer was validated when parsing");
+        let new = buffer_str(&data).expect("clone of a buffer contains the same bytes");
+        let value = rebase(&self.value, old, new);
+        let value = unsafe { extend_lifetime(value) };
+        Self { data, value }
     }
 }
 
-impl Deref for OwnedValue {
+impl<B> Deref for OwnedValue<B> {
     type Target = Value<'static>;
 
     fn deref(&self) -> &Self::Target {
@@ -76,6 +320,142 @@ impl Deref for OwnedValue {
     }
 }
 
+/// A buffer for parsing many JSON documents one after the other, e.g. the lines of a log file,
+/// which reuses its allocations between documents.
+///
+/// Like [`OwnedValue`], the buffer keeps a copy of the input, from which the [`Value`] borrows.
+/// Parsing the next document reuses the `String` of the previous input, and the `Vec`s of the
+/// arrays and objects of the previous `Value`. Documents are parsed with
+/// [`from_str`](crate::from_str), or with [`from_str_with_policy`](crate::from_str_with_policy)
+/// after [`ValueBuffer::with_policy`].
+///
+/// ## Example
+/// ```
+/// use serde_json_borrow::ValueBuffer;
+///
+/// let mut buffer = ValueBuffer::new();
+/// let mut total = 0;
+/// for line in [r#"{"id": 1, "tags": ["a"]}"#, r#"{"id": 2, "tags": ["b", "c"]}"#] {
+///     let value = buffer.parse_str(line).unwrap();
+///     total += value.get("tags").as_array().unwrap().len();
+/// }
+/// assert_eq!(total, 3);
+/// ```
+#[derive(Default)]
+pub struct ValueBuffer {
+    data: String,
+    /// Borrows from `data`, which is only modified after the value was recycled.
+    value: Value<'static>,
+    pool: VecPool<'static>,
+    policy: DuplicateKeyPolicy,
+}
+
+impl ValueBuffer {
+    /// Creates an empty buffer, which contains `Value::Null`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Handles duplicate keys in objects according to `policy`. By default, all entries are
+    /// kept.
+    pub fn with_policy(self, policy: DuplicateKeyPolicy) -> Self {
+        ValueBuffer { policy, ..self }
+    }
+
+    /// Copies `json` into the buffer and parses it into a [`Value`], replacing the previous
+    /// value.
+    pub fn parse_str(&mut self, json: &str) -> Result<&Value<'_>, Error> {
+        self.recycle();
+        self.data.push_str(json);
+        self.parse()
+    }
+
+    /// Validates `&[u8]` for utf-8, copies it into the buffer and parses it into a [`Value`],
+    /// replacing the previous value.
+    pub fn parse_slice(&mut self, json: &[u8]) -> Result<&Value<'_>, Error> {
+        self.recycle();
+        self.data.push_str(from_utf8(json)?);
+        self.parse()
+    }
+
+    /// Returns the last parsed value, or `Value::Null` if the last document failed to parse.
+    pub fn value(&self) -> &Value<'_> {
+        &self.value
+    }
+
+    fn recycle(&mut self) {
+        self.pool.recycle(std::mem::take(&mut self.value));
+        self.data.clear();
+    }
+
+    fn parse(&mut self) -> Result<&Value<'_>, Error> {
+        let json: *const str = self.data.as_str();
+        // SAFETY: The contents of `data` are on the heap and are not modified until the value
+        // that borrows from it is recycled. The value is only handed out with the lifetime of
+        // `self`.
+        let json: &'static str = unsafe { &*json };
+        let mut parser =
+            Parser::with_pool(json, std::mem::take(&mut self.pool)).with_policy(self.policy);
+        let value = parser.parse_value().and_then(|value| {
+            parser.end()?;
+            Ok(value)
+        });
+        self.pool = parser.into_pool();
+        self.value = value?;
+        Ok(&self.value)
+    }
+}
+
+impl fmt::Debug for ValueBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ValueBuffer")
+            .field("value", &self.value)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Copies `value`, replacing strings borrowed from `old` with the same slices of `new`.
+///
+/// Strings which are not borrowed from `old` are kept.
+fn rebase<'a>(value: &Value<'a>, old: &str, new: &'a str) -> Value<'a> {
+    match value {
+        Value::Str(Cow::Borrowed(s)) => Value::Str(Cow::Borrowed(rebase_str(s, old, new))),
+        Value::Array(values) => {
+            Value::Array(values.iter().map(|value| rebase(value, old, new)).collect())
+        }
+        Value::Object(object) => {
+            let entries: Vec<_> = object
+                .as_vec()
+                .iter()
+                .map(|(key, value)| {
+                    let key = match &key.0 {
+                        Cow::Borrowed(key) => rebase_str(key, old, new).into(),
+                        Cow::Owned(key) => key.clone().into(),
+                    };
+                    (key, rebase(value, old, new))
+                })
+                .collect();
+            let index = object.1.rebuild_for(&entries);
+            Value::Object(ObjectAsVec(entries, index))
+        }
+        #[cfg(feature = "arbitrary_precision")]
+        Value::Number(number) => {
+            Value::Number(number.map_lexeme(|lexeme| rebase_str(lexeme, old, new)))
+        }
+        _ => value.clone(),
+    }
+}
+
+fn rebase_str<'a>(s: &'a str, old: &str, new: &'a str) -> &'a str {
+    let old_range = old.as_bytes().as_ptr_range();
+    if old_range.contains(&s.as_ptr()) {
+        let start = s.as_ptr() as usize - old.as_ptr() as usize;
+        &new[start..start + s.len()]
+    } else {
+        s
+    }
+}
+
 unsafe fn extend_lifetime<'b>(r: Value<'b>) -> Value<'static> {
     std::mem::transmute::<Value<'b>, Value<'static>>(r)
 }
@@ -104,4 +484,176 @@ mod tests {
         assert_eq!(owned_value.get("name"), &Value::Str("John".into()));
         assert_eq!(owned_value.get("age"), &Value::Number(30_u64.into()));
     }
+
+    /// Test that a clone borrows from its own buffer, if cloning the buffer copies it.
+    #[test]
+    fn test_clone_copied_buffer() {
+        let raw_json = r#"{"name": "John", "tags": ["a", "b\n"], "key\"": {"k": "v"}}"#;
+        let owned_value = OwnedValue::from_buffer(raw_json.to_string()).unwrap();
+        let clone = owned_value.clone();
+        drop(owned_value);
+        assert_eq!(clone.value, crate::from_str(raw_json).unwrap());
+        let range = clone.data.as_bytes().as_ptr_range();
+        let Value::Str(Cow::Borrowed(name)) = clone.get("name") else {
+            panic!("expected a borrowed string");
+        };
+        assert!(range.contains(&name.as_ptr()));
+        let (key, _) = &clone.get("key\"").as_object().unwrap().as_vec()[0];
+        assert!(range.contains(&key.as_ptr()));
+
+        let owned_value =
+            OwnedValue::from_buffer(Box::<str>::from(r#"["x", {"y": "z"}]"#)).unwrap();
+        let clone = owned_value.clone();
+        drop(owned_value);
+        assert_eq!(clone.get(1).get("y"), &Value::Str("z".into()));
+
+        let owned_value = OwnedValue::from_value(Value::Str("static".into()));
+        assert_eq!(owned_value.clone().as_str(), Some("static"));
+    }
+
+    /// Test that numbers which keep their text borrow from the clone's buffer.
+    #[cfg(feature = "arbitrary_precision")]
+    #[test]
+    fn test_clone_copied_buffer_lexeme() {
+        let json = String::from("[1.10000000000000000001]");
+        let owned_value = OwnedValue::from_buffer(json).unwrap();
+        let clone = owned_value.clone();
+        drop(owned_value);
+        assert_eq!(format!("{:?}", clone.get(0)), "Number(1.10000000000000000001)");
+        assert_eq!(
+            serde_json::to_string(clone.get_value()).unwrap(),
+            "[1.10000000000000000001]"
+        );
+    }
+
+    /// Test that all constructors parsing JSON keep the text of numbers.
+    #[cfg(feature = "arbitrary_precision")]
+    #[test]
+    fn test_constructors_keep_lexemes() {
+        let json = "[1.10,340282366920938463463374607431768211456]";
+        let values = [
+            OwnedValue::from_str(json).unwrap(),
+            OwnedValue::from_string(json.to_string()).unwrap(),
+            OwnedValue::from_slice(json.as_bytes()).unwrap(),
+            OwnedValue::from_vec(json.as_bytes().to_vec()).unwrap(),
+            OwnedValue::from_reader(json.as_bytes()).unwrap(),
+            OwnedValue::from_reader_with_limit(json.as_bytes(), 1024).unwrap(),
+            OwnedValue::from_str_with_policy(json, DuplicateKeyPolicy::Error).unwrap(),
+            OwnedValue::from_slice_with_policy(json.as_bytes(), DuplicateKeyPolicy::KeepLast)
+                .unwrap(),
+        ];
+        for value in values {
+            assert_eq!(serde_json::to_string(value.get_value()).unwrap(), json);
+        }
+    }
+
+    #[test]
+    fn test_from_buffer() {
+        let raw_json: Arc<str> = r#"{"name": "John"}"#.into();
+        let owned_value = OwnedValue::from_buffer(raw_json.clone()).unwrap();
+        let clone = owned_value.clone();
+        assert!(Arc::ptr_eq(clone.buffer(), &raw_json));
+        assert_eq!(clone.get_value(), owned_value.get_value());
+
+        let owned_value = OwnedValue::from_buffer(br#"[1, "a"]"#.to_vec()).unwrap();
+        assert_eq!(owned_value.get(1), &Value::Str("a".into()));
+        let err = OwnedValue::from_buffer(b"\"\xff\"".to_vec()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(OwnedValue::from_buffer(String::from("[1,]")).is_err());
+    }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn test_from_bytes() {
+        let raw_json = bytes::Bytes::from_static(br#"{"name": "John"}"#);
+        let owned_value = OwnedValue::from_buffer(raw_json.clone()).unwrap();
+        let clone = owned_value.clone();
+        drop(owned_value);
+        assert_eq!(clone.buffer().as_ptr(), raw_json.as_ptr());
+        assert_eq!(clone.get("name"), &Value::Str("John".into()));
+    }
+
+    #[test]
+    fn test_from_reader() {
+        let raw_json = r#"{"name": "John"}"#;
+        let owned_value = OwnedValue::from_reader(raw_json.as_bytes()).unwrap();
+        assert_eq!(owned_value.get("name"), &Value::Str("John".into()));
+        let owned_value = OwnedValue::from_reader_with_limit(raw_json.as_bytes(), 16).unwrap();
+        assert_eq!(owned_value.get("name"), &Value::Str("John".into()));
+
+        let err = OwnedValue::from_reader_with_limit(raw_json.as_bytes(), 15).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "input exceeds the size limit of 15 bytes");
+        let err = err
+            .into_inner()
+            .unwrap()
+            .downcast::<LimitExceeded>()
+            .unwrap();
+        assert_eq!(err.limit(), 15);
+
+        let mut reader = io::repeat(b' ');
+        assert!(OwnedValue::from_reader_with_limit(&mut reader, 1 << 20).is_err());
+        assert!(OwnedValue::from_reader(&b"[1,]"[..]).is_err());
+        assert!(OwnedValue::from_reader(&b"\"\xff\""[..]).is_err());
+    }
+
+    #[test]
+    fn test_from_vec() {
+        let data = br#"{"name": "John"}"#.to_vec();
+        let ptr = data.as_ptr();
+        let owned_value = OwnedValue::from_vec(data).unwrap();
+        assert_eq!(owned_value.data.as_ptr(), ptr);
+        assert_eq!(owned_value.get("name"), &Value::Str("John".into()));
+        assert!(OwnedValue::from_vec(b"\"\xff\"".to_vec()).is_err());
+    }
+
+    #[test]
+    fn test_value_buffer() {
+        let mut buffer = ValueBuffer::new();
+        assert_eq!(buffer.value(), &Value::Null);
+        let value = buffer
+            .parse_str(r#"{"a": [1, {"b": "c"}], "d": [[], {}]}"#)
+            .unwrap();
+        assert_eq!(value.get("a").get(1).get("b"), &Value::Str("c".into()));
+        let data = buffer.data.as_ptr();
+
+        let value = buffer.parse_slice(br#"[{"e": "f"}, [2]]"#).unwrap();
+        assert_eq!(value, &crate::from_str(r#"[{"e": "f"}, [2]]"#).unwrap());
+        assert_eq!(buffer.data.as_ptr(), data);
+
+        let err = buffer.parse_str("[1,]").unwrap_err();
+        assert_eq!(err.message(), "trailing comma");
+        assert_eq!(buffer.value(), &Value::Null);
+        assert!(buffer.parse_slice(b"\"\xff\"").is_err());
+
+        let value = buffer.parse_str(r#" {"g": [3, 4]} "#).unwrap();
+        assert_eq!(value.get("g").get(1), &Value::from(4u64));
+    }
+
+    #[test]
+    fn test_value_buffer_policy() {
+        let mut buffer = ValueBuffer::new().with_policy(DuplicateKeyPolicy::KeepLast);
+        let value = buffer.parse_str(r#"{"a": 1, "b": 2, "a": 3}"#).unwrap();
+        assert_eq!(value, &crate::from_str(r#"{"a": 3, "b": 2}"#).unwrap());
+
+        let mut buffer = ValueBuffer::new().with_policy(DuplicateKeyPolicy::Error);
+        let err = buffer.parse_str(r#"[{"a": 1, "a": 2}]"#).unwrap_err();
+        assert_eq!((err.message(), err.column()), ("duplicate key `a`", 17));
+        let value = buffer.parse_str(r#"{"a": 1}"#).unwrap();
+        assert_eq!(value.get("a"), &Value::from(1u64));
+    }
+
+    #[test]
+    fn test_duplicate_key_policy() {
+        let raw_json = r#"{"a": 1, "a": 2}"#;
+        let owned_value =
+            OwnedValue::from_str_with_policy(raw_json, DuplicateKeyPolicy::KeepLast).unwrap();
+        assert_eq!(owned_value.get("a"), &Value::Number(2_u64.into()));
+        let owned_value =
+            OwnedValue::from_slice_with_policy(raw_json.as_bytes(), DuplicateKeyPolicy::KeepFirst)
+                .unwrap();
+        assert_eq!(owned_value.get("a"), &Value::Number(1_u64.into()));
+        assert!(OwnedValue::from_str_with_policy(raw_json, DuplicateKeyPolicy::Error).is_err());
+        assert!(OwnedValue::from_str_with_policy("{} {}", DuplicateKeyPolicy::KeepAll).is_err());
+    }
 }
@@ -0,0 +1,979 @@
+use std::borrow::Cow;
+
+#[cfg(feature = "bumpalo")]
+use bumpalo::Bump;
+
+#[cfg(feature = "bumpalo")]
+use crate::arena::{ArenaScratch, ArenaValue};
+use crate::de::EntryCollector;
+use crate::object_vec::{KeyStrType, ObjectAsVec};
+use crate::{DuplicateKeyPolicy, Error, Number, Value};
+
+/// Maximum nesting depth of arrays and objects, same as serde_json.
+pub(crate) const RECURSION_LIMIT: u8 = 128;
+
+/// Parses a JSON string into a [`Value`], borrowing from the input wherever possible.
+///
+/// Unlike deserializing via `serde_json::from_str`, this parser builds the [`Value`] directly.
+/// All strings and keys without escape sequences are borrowed from the input, strings and keys
+/// containing escape sequences are unescaped into owned strings. This is independent of the
+/// `cowkeys` feature flag.
+///
+/// Syntax errors report the position in the input via [`Error::line`] and [`Error::column`].
+/// Nesting is limited to a depth of 128, like in serde_json. All entries of objects are kept,
+/// including duplicate keys, see [`from_str_with_policy`] to handle them differently.
+///
+/// ## Example
+/// ```
+/// use std::borrow::Cow;
+///
+/// use serde_json_borrow::Value;
+///
+/// let value = serde_json_borrow::from_str(r#"{"name": "John", "tag\"": "a\nb"}"#).unwrap();
+/// assert_eq!(value.get("name"), &Value::Str(Cow::Borrowed("John")));
+/// assert_eq!(value.get("tag\""), &Value::Str(Cow::Owned("a\nb".to_string())));
+///
+/// let err = serde_json_borrow::from_str("[1,\n 2,]").unwrap_err();
+/// assert_eq!(err.to_string(), "trailing comma at line 2 column 4");
+/// ```
+pub fn from_str(json: &str) -> Result<Value<'_>, Error> {
+    let mut parser = Parser::new(json);
+    let value = parser.parse_value()?;
+    parser.end()?;
+    Ok(value)
+}
+
+/// Validates `&[u8]` for utf-8 and parses it into a [`Value`], borrowing from the input wherever
+/// possible.
+///
+/// See [`from_str`].
+pub fn from_slice(json: &[u8]) -> Result<Value<'_>, Error> {
+    from_str(from_utf8(json)?)
+}
+
+/// Parses a JSON string into a [`Value`] like [`from_str`], handling duplicate keys in objects
+/// according to `policy`.
+///
+/// ## Example
+/// ```
+/// use serde_json_borrow::{DuplicateKeyPolicy, Value};
+///
+/// let json = r#"{"a": 1, "a": 2}"#;
+/// let value = serde_json_borrow::from_str_with_policy(json, DuplicateKeyPolicy::KeepLast);
+/// assert_eq!(value.unwrap().get("a"), &Value::from(2u64));
+/// let err = serde_json_borrow::from_str_with_policy(json, DuplicateKeyPolicy::Error);
+/// assert_eq!(err.unwrap_err().to_string(), "duplicate key `a` at line 1 column 16");
+/// ```
+pub fn from_str_with_policy(json: &str, policy: DuplicateKeyPolicy) -> Result<Value<'_>, Error> {
+    let mut parser = Parser::new(json).with_policy(policy);
+    let value = parser.parse_value()?;
+    parser.end()?;
+    Ok(value)
+}
+
+/// Validates `&[u8]` for utf-8 and parses it into a [`Value`], handling duplicate keys in
+/// objects according to `policy`.
+///
+/// See [`from_str_with_policy`].
+pub fn from_slice_with_policy(json: &[u8], policy: DuplicateKeyPolicy) -> Result<Value<'_>, Error> {
+    from_str_with_policy(from_utf8(json)?, policy)
+}
+
+/// Validates `&[u8]` for utf-8, reporting the position of invalid data like a syntax error.
+pub(crate) fn from_utf8(json: &[u8]) -> Result<&str, Error> {
+    std::str::from_utf8(json).map_err(|err| {
+        let (line, column) = line_column(json, err.valid_up_to());
+        Error::syntax("invalid UTF-8", line, column)
+    })
+}
+
+/// Returns the one-based line and column of the byte at `pos`, like serde_json a newline is
+/// reported as column 0 of the following line.
+fn line_column(bytes: &[u8], pos: usize) -> (usize, usize) {
+    let consumed = &bytes[..bytes.len().min(pos + 1)];
+    let line = 1 + consumed.iter().filter(|&&b| b == b'\n').count();
+    let line_start = consumed
+        .iter()
+        .rposition(|&b| b == b'\n')
+        .map_or(0, |i| i + 1);
+    (line, consumed.len() - line_start)
+}
+
+/// Bytes which end the fast path of string scanning: `"`, `\` and control characters.
+static STOP: [bool; 256] = {
+    let mut table = [false; 256];
+    let mut i = 0;
+    while i < 0x20 {
+        table[i] = true;
+        i += 1;
+    }
+    table[b'"' as usize] = true;
+    table[b'\\' as usize] = true;
+    table
+};
+
+/// Empty `Vec`s of previously parsed arrays and objects, which are reused for new arrays and
+/// objects to avoid allocations.
+#[derive(Default)]
+pub(crate) struct VecPool<'ctx> {
+    arrays: Vec<Vec<Value<'ctx>>>,
+    objects: Vec<Vec<(KeyStrType<'ctx>, Value<'ctx>)>>,
+}
+
+impl<'ctx> VecPool<'ctx> {
+    /// Takes apart `value` and keeps the `Vec`s of its arrays and objects.
+    pub(crate) fn recycle(&mut self, value: Value<'ctx>) {
+        match value {
+            Value::Array(mut values) => {
+                for value in values.drain(..) {
+                    self.recycle(value);
+                }
+                self.arrays.push(values);
+            }
+            Value::Object(object) => {
+                let mut entries = object.0;
+                for (_, value) in entries.drain(..) {
+                    self.recycle(value);
+                }
+                self.objects.push(entries);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// A recursive descent JSON parser producing [`Value`]s that borrow from the input.
+pub(crate) struct Parser<'ctx> {
+    input: &'ctx str,
+    pos: usize,
+    remaining_depth: u8,
+    pool: VecPool<'ctx>,
+    policy: DuplicateKeyPolicy,
+}
+
+impl<'ctx> Parser<'ctx> {
+    pub(crate) fn new(input: &'ctx str) -> Self {
+        Self::with_pool(input, VecPool::default())
+    }
+
+    /// Creates a parser which takes the `Vec`s for arrays and objects from `pool`.
+    pub(crate) fn with_pool(input: &'ctx str, pool: VecPool<'ctx>) -> Self {
+        Parser {
+            input,
+            pos: 0,
+            remaining_depth: RECURSION_LIMIT,
+            pool,
+            policy: DuplicateKeyPolicy::KeepAll,
+        }
+    }
+
+    /// Handles duplicate keys in objects according to `policy`. Arena values always keep all
+    /// entries.
+    pub(crate) fn with_policy(mut self, policy: DuplicateKeyPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Returns the pool with the `Vec`s that were not used.
+    pub(crate) fn into_pool(self) -> VecPool<'ctx> {
+        self.pool
+    }
+
+    /// The byte offset of the next unparsed character.
+    pub(crate) fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Skips whitespace and returns true if there is no more input.
+    pub(crate) fn at_end(&mut self) -> bool {
+        self.skip_whitespace();
+        self.peek().is_none()
+    }
+
+    /// Checks that the next character can follow a value in a stream of values.
+    ///
+    /// Numbers and `true`, `false` and `null` need to be separated from the next value, e.g.
+    /// `1 2` are two values, `12` is one and `1true` is an error.
+    pub(crate) fn end_of_stream_value(&self) -> Result<(), Error> {
+        match self.peek() {
+            None | Some(b' ' | b'\n' | b'\t' | b'\r' | b'"' | b'[' | b'{') => Ok(()),
+            Some(_) => Err(self.error("trailing characters")),
+        }
+    }
+
+    /// Checks that only whitespace follows the parsed value.
+    pub(crate) fn end(&mut self) -> Result<(), Error> {
+        self.skip_whitespace();
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(self.error("trailing characters")),
+        }
+    }
+
+    /// Parses the input as a single number, without surrounding whitespace. Numbers out of the
+    /// range of `f64` are rejected, like without the `arbitrary_precision` feature flag.
+    pub(crate) fn parse_number_only(&mut self) -> Result<Number<'ctx>, Error> {
+        let number = self.parse_number()?;
+        if number.as_f64().is_none() {
+            self.pos -= 1;
+            return Err(self.error("number out of range"));
+        }
+        match self.peek() {
+            None => Ok(number),
+            Some(_) => Err(self.error("trailing characters")),
+        }
+    }
+
+    /// Parses the next value, with optional leading whitespace.
+    pub(crate) fn parse_value(&mut self) -> Result<Value<'ctx>, Error> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'"') => {
+                self.pos += 1;
+                Ok(Value::Str(self.parse_str()?))
+            }
+            Some(b'{') => self.parse_object(),
+            Some(b'[') => self.parse_array(),
+            Some(b'-' | b'0'..=b'9') => Ok(Value::Number(self.parse_number()?)),
+            Some(b't') => self.parse_ident(b"true", Value::Bool(true)),
+            Some(b'f') => self.parse_ident(b"false", Value::Bool(false)),
+            Some(b'n') => self.parse_ident(b"null", Value::Null),
+            Some(_) => Err(self.error("expected value")),
+            None => Err(self.eof_error("EOF while parsing a value")),
+        }
+    }
+
+    #[inline]
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.pos).copied()
+    }
+
+    #[inline]
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\n' | b'\t' | b'\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    /// An error at the current position.
+    fn error(&self, msg: &str) -> Error {
+        let (line, column) = line_column(self.input.as_bytes(), self.pos);
+        Error::syntax(msg, line, column)
+    }
+
+    /// An error at the last character of the input.
+    fn eof_error(&self, msg: &str) -> Error {
+        let (line, column) = line_column(self.input.as_bytes(), self.input.len());
+        Error::syntax(msg, line, column)
+    }
+
+    fn enter(&mut self) -> Result<(), Error> {
+        if self.remaining_depth == 0 {
+            return Err(self.error("recursion limit exceeded"));
+        }
+        self.remaining_depth -= 1;
+        Ok(())
+    }
+
+    fn parse_ident<T>(&mut self, ident: &[u8], value: T) -> Result<T, Error> {
+        for &expected in ident {
+            match self.peek() {
+                Some(b) if b == expected => self.pos += 1,
+                Some(_) => return Err(self.error("expected ident")),
+                None => return Err(self.eof_error("EOF while parsing a value")),
+            }
+        }
+        Ok(value)
+    }
+
+    fn parse_array(&mut self) -> Result<Value<'ctx>, Error> {
+        self.enter()?;
+        self.pos += 1;
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            self.remaining_depth += 1;
+            return Ok(Value::Array(Vec::new()));
+        }
+        let mut values = self.pool.arrays.pop().unwrap_or_default();
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some(b']') {
+                return Err(self.error("trailing comma"));
+            }
+            values.push(self.parse_value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(_) => return Err(self.error("expected `,` or `]`")),
+                None => return Err(self.eof_error("EOF while parsing a list")),
+            }
+        }
+        self.remaining_depth += 1;
+        Ok(Value::Array(values))
+    }
+
+    fn parse_object(&mut self) -> Result<Value<'ctx>, Error> {
+        self.enter()?;
+        self.pos += 1;
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            self.remaining_depth += 1;
+            return Ok(Value::Object(ObjectAsVec::default()));
+        }
+        let mut entries = self.pool.objects.pop().unwrap_or_default();
+        let mut collector = EntryCollector::new(self.policy);
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b'"') => self.pos += 1,
+                Some(b'}') => return Err(self.error("trailing comma")),
+                Some(_) => return Err(self.error("key must be a string")),
+                None => return Err(self.eof_error("EOF while parsing an object")),
+            }
+            let key = self.parse_str()?;
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b':') => self.pos += 1,
+                Some(_) => return Err(self.error("expected `:`")),
+                None => return Err(self.eof_error("EOF while parsing an object")),
+            }
+            let value = self.parse_value()?;
+            if let Err(key) = collector.push(&mut entries, key.into(), value) {
+                return Err(self.error(&format!("duplicate key `{}`", &*key)));
+            }
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(_) => return Err(self.error("expected `,` or `}`")),
+                None => return Err(self.eof_error("EOF while parsing an object")),
+            }
+        }
+        self.remaining_depth += 1;
+        Ok(Value::Object(ObjectAsVec::from_entries(entries)))
+    }
+
+    /// Scans until the next `"`, `\` or control character.
+    #[inline]
+    fn scan_str(&mut self) {
+        const ONES: u64 = u64::from_ne_bytes([0x01; 8]);
+        const HIGH: u64 = u64::from_ne_bytes([0x80; 8]);
+        const QUOTE: u64 = u64::from_ne_bytes([b'"'; 8]);
+        const BACKSLASH: u64 = u64::from_ne_bytes([b'\\'; 8]);
+        const SPACE: u64 = u64::from_ne_bytes([0x20; 8]);
+
+        let bytes = self.input.as_bytes();
+        // Check 8 bytes at a time. A byte in `found` has its high bit set if the corresponding
+        // input byte is a quote, a backslash or a control character. Only the lowest flagged
+        // byte is exact, which is all we need.
+        while let Some(chunk) = bytes.get(self.pos..self.pos + 8) {
+            let chunk = u64::from_le_bytes(chunk.try_into().unwrap());
+            let quote = chunk ^ QUOTE;
+            let backslash = chunk ^ BACKSLASH;
+            let found = (quote.wrapping_sub(ONES) & !quote)
+                | (backslash.wrapping_sub(ONES) & !backslash)
+                | (chunk.wrapping_sub(SPACE) & !chunk);
+            let found = found & HIGH;
+            if found != 0 {
+                self.pos += found.trailing_zeros() as usize / 8;
+                return;
+            }
+            self.pos += 8;
+        }
+        while self.pos < bytes.len() && !STOP[bytes[self.pos] as usize] {
+            self.pos += 1;
+        }
+    }
+
+    /// Parses a string after the opening quote. Borrows from the input if the string contains no
+    /// escape sequences.
+    fn parse_str(&mut self) -> Result<Cow<'ctx, str>, Error> {
+        let start = self.pos;
+        self.scan_str();
+        let mut unescaped = match self.peek() {
+            Some(b'"') => {
+                let s = &self.input[start..self.pos];
+                self.pos += 1;
+                return Ok(Cow::Borrowed(s));
+            }
+            Some(b'\\') => String::with_capacity(self.pos - start + 16),
+            Some(_) => {
+                return Err(
+                    self.error("control character (\\u0000-\\u001F) found while parsing a string")
+                )
+            }
+            None => return Err(self.eof_error("EOF while parsing a string")),
+        };
+        unescaped.push_str(&self.input[start..self.pos]);
+        loop {
+            // At a backslash.
+            self.pos += 1;
+            self.parse_escape(&mut unescaped)?;
+            let chunk_start = self.pos;
+            self.scan_str();
+            unescaped.push_str(&self.input[chunk_start..self.pos]);
+            match self.peek() {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(Cow::Owned(unescaped));
+                }
+                Some(b'\\') => {}
+                Some(_) => {
+                    return Err(self
+                        .error("control character (\\u0000-\\u001F) found while parsing a string"))
+                }
+                None => return Err(self.eof_error("EOF while parsing a string")),
+            }
+        }
+    }
+
+    /// Parses an escape sequence after the backslash.
+    fn parse_escape(&mut self, out: &mut String) -> Result<(), Error> {
+        let Some(b) = self.peek() else {
+            return Err(self.eof_error("EOF while parsing a string"));
+        };
+        let c = match b {
+            b'"' => '"',
+            b'\\' => '\\',
+            b'/' => '/',
+            b'b' => '\x08',
+            b'f' => '\x0c',
+            b'n' => '\n',
+            b'r' => '\r',
+            b't' => '\t',
+            b'u' => {
+                self.pos += 1;
+                let c = self.parse_unicode_escape()?;
+                out.push(c);
+                return Ok(());
+            }
+            _ => return Err(self.error("invalid escape")),
+        };
+        self.pos += 1;
+        out.push(c);
+        Ok(())
+    }
+
+    /// Parses the code point of a `\u` escape, including a following low surrogate.
+    fn parse_unicode_escape(&mut self) -> Result<char, Error> {
+        let n = self.parse_hex4()?;
+        let code_point = match n {
+            0xD800..=0xDBFF => {
+                if self.input.as_bytes()[self.pos..].starts_with(b"\\u") {
+                    self.pos += 2;
+                } else {
+                    return Err(self.error("lone leading surrogate in hex escape"));
+                }
+                let n2 = self.parse_hex4()?;
+                if !(0xDC00..=0xDFFF).contains(&n2) {
+                    return Err(self.error("lone leading surrogate in hex escape"));
+                }
+                0x10000 + (((n - 0xD800) << 10) | (n2 - 0xDC00))
+            }
+            n => n,
+        };
+        char::from_u32(code_point).ok_or_else(|| self.error("invalid unicode code point"))
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, Error> {
+        let mut n = 0;
+        for _ in 0..4 {
+            let digit = match self.peek() {
+                Some(b) => (b as char)
+                    .to_digit(16)
+                    .ok_or_else(|| self.error("invalid escape"))?,
+                None => return Err(self.eof_error("EOF while parsing a string")),
+            };
+            n = (n << 4) | digit;
+            self.pos += 1;
+        }
+        Ok(n)
+    }
+
+    fn skip_digits(&mut self) {
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    /// Parses a number. Integers are stored as 64-bit or 128-bit integers if they fit,
+    /// everything else as `f64`. With the `arbitrary_precision` feature flag, everything else is
+    /// stored with its text as well, including numbers out of the range of `f64`.
+    fn parse_number(&mut self) -> Result<Number<'ctx>, Error> {
+        let start = self.pos;
+        let negative = self.peek() == Some(b'-');
+        if negative {
+            self.pos += 1;
+        }
+        match self.peek() {
+            Some(b'0') => self.pos += 1,
+            Some(b'1'..=b'9') => self.skip_digits(),
+            Some(_) => return Err(self.error("invalid number")),
+            None => return Err(self.eof_error("EOF while parsing a value")),
+        }
+        let int_end = self.pos;
+        let mut is_float = false;
+        if self.peek() == Some(b'.') {
+            is_float = true;
+            self.pos += 1;
+            match self.peek() {
+                Some(b'0'..=b'9') => self.skip_digits(),
+                Some(_) => return Err(self.error("invalid number")),
+                None => return Err(self.eof_error("EOF while parsing a value")),
+            }
+        }
+        if let Some(b'e' | b'E') = self.peek() {
+            is_float = true;
+            self.pos += 1;
+            if let Some(b'+' | b'-') = self.peek() {
+                self.pos += 1;
+            }
+            match self.peek() {
+                Some(b'0'..=b'9') => self.skip_digits(),
+                Some(_) => return Err(self.error("invalid number")),
+                None => return Err(self.eof_error("EOF while parsing a value")),
+            }
+        }
+
+        if !is_float {
+            let digits = &self.input.as_bytes()[start + negative as usize..int_end];
+            if let Some(n) = parse_u64(digits) {
+                if !negative {
+                    return Ok(n.into());
+                }
+                // Like serde_json, `-0` is parsed as float below to keep the sign.
+                if n != 0 && n <= i64::MAX as u64 + 1 {
+                    return Ok((n as i64).wrapping_neg().into());
+                }
+            }
+            if let Ok(n) = self.input[start + negative as usize..int_end].parse::<u128>() {
+                if !negative {
+                    return Ok(n.into());
+                }
+                if n != 0 && n <= i128::MAX as u128 + 1 {
+                    return Ok((n as i128).wrapping_neg().into());
+                }
+            }
+        }
+        // Floats, `-0`, and integers which don't fit into 128 bits.
+        let lexeme = &self.input[start..self.pos];
+        #[cfg(feature = "arbitrary_precision")]
+        if let Ok(f) = lexeme.parse::<f64>() {
+            // Integers beyond 128 bits are never zero, so zero can only be `-0`.
+            return Ok(Number::from_lexeme(lexeme, f, is_float || f == 0.0));
+        }
+        #[cfg(not(feature = "arbitrary_precision"))]
+        if let Some(number) = lexeme.parse().ok().and_then(Number::from_f64) {
+            return Ok(number);
+        }
+        // Report the error at the last digit, like serde_json.
+        self.pos -= 1;
+        Err(self.error("number out of range"))
+    }
+}
+
+#[cfg(feature = "bumpalo")]
+impl<'ctx> Parser<'ctx> {
+    /// Parses the next value into `arena`, like [`Parser::parse_value`].
+    ///
+    /// The elements of arrays and objects are collected on the stacks of `scratch`, and moved
+    /// into a slice in the arena once the array or object is complete.
+    pub(crate) fn parse_arena_value(
+        &mut self,
+        arena: &'ctx Bump,
+        scratch: &mut ArenaScratch<'ctx>,
+    ) -> Result<ArenaValue<'ctx>, Error> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'"') => {
+                self.pos += 1;
+                Ok(ArenaValue::Str(self.parse_arena_str(arena)?))
+            }
+            Some(b'{') => self.parse_arena_object(arena, scratch),
+            Some(b'[') => self.parse_arena_array(arena, scratch),
+            Some(b'-' | b'0'..=b'9') => Ok(ArenaValue::Number(self.parse_number()?)),
+            Some(b't') => self.parse_ident(b"true", ArenaValue::Bool(true)),
+            Some(b'f') => self.parse_ident(b"false", ArenaValue::Bool(false)),
+            Some(b'n') => self.parse_ident(b"null", ArenaValue::Null),
+            Some(_) => Err(self.error("expected value")),
+            None => Err(self.eof_error("EOF while parsing a value")),
+        }
+    }
+
+    fn parse_arena_str(&mut self, arena: &'ctx Bump) -> Result<&'ctx str, Error> {
+        Ok(match self.parse_str()? {
+            Cow::Borrowed(s) => s,
+            Cow::Owned(s) => arena.alloc_str(&s),
+        })
+    }
+
+    fn parse_arena_array(
+        &mut self,
+        arena: &'ctx Bump,
+        scratch: &mut ArenaScratch<'ctx>,
+    ) -> Result<ArenaValue<'ctx>, Error> {
+        self.enter()?;
+        self.pos += 1;
+        let start = scratch.values.len();
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            self.remaining_depth += 1;
+            return Ok(ArenaValue::Array(&[]));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some(b']') {
+                return Err(self.error("trailing comma"));
+            }
+            let value = self.parse_arena_value(arena, scratch)?;
+            scratch.values.push(value);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(_) => return Err(self.error("expected `,` or `]`")),
+                None => return Err(self.eof_error("EOF while parsing a list")),
+            }
+        }
+        self.remaining_depth += 1;
+        let values = arena.alloc_slice_fill_iter(scratch.values.drain(start..));
+        Ok(ArenaValue::Array(values))
+    }
+
+    fn parse_arena_object(
+        &mut self,
+        arena: &'ctx Bump,
+        scratch: &mut ArenaScratch<'ctx>,
+    ) -> Result<ArenaValue<'ctx>, Error> {
+        self.enter()?;
+        self.pos += 1;
+        let start = scratch.entries.len();
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            self.remaining_depth += 1;
+            return Ok(ArenaValue::Object(&[]));
+        }
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b'"') => self.pos += 1,
+                Some(b'}') => return Err(self.error("trailing comma")),
+                Some(_) => return Err(self.error("key must be a string")),
+                None => return Err(self.eof_error("EOF while parsing an object")),
+            }
+            let key = self.parse_arena_str(arena)?;
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b':') => self.pos += 1,
+                Some(_) => return Err(self.error("expected `:`")),
+                None => return Err(self.eof_error("EOF while parsing an object")),
+            }
+            let value = self.parse_arena_value(arena, scratch)?;
+            scratch.entries.push((key, value));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(_) => return Err(self.error("expected `,` or `}`")),
+                None => return Err(self.eof_error("EOF while parsing an object")),
+            }
+        }
+        self.remaining_depth += 1;
+        let entries = arena.alloc_slice_fill_iter(scratch.entries.drain(start..));
+        Ok(ArenaValue::Object(entries))
+    }
+}
+
+/// Parses ASCII digits into a `u64`, returning `None` on overflow.
+#[inline]
+fn parse_u64(digits: &[u8]) -> Option<u64> {
+    let mut n: u64 = 0;
+    for &d in digits {
+        n = n.checked_mul(10)?.checked_add((d - b'0') as u64)?;
+    }
+    Some(n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_same_as_serde_json(json: &str) {
+        let value = from_str(json).unwrap();
+        let expected: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(serde_json::Value::from(&value), expected, "{}", json);
+        // The result should be the same as deserializing via serde.
+        let via_serde: serde_json::Value = serde_json::from_str::<Value>(json).unwrap().into();
+        assert_eq!(via_serde, expected, "{}", json);
+    }
+
+    #[test]
+    fn test_parse_values() {
+        for json in [
+            "null",
+            "true",
+            " false ",
+            "0",
+            "-0",
+            "-0.0",
+            "123",
+            "-123",
+            "1.5",
+            "1e3",
+            "1E-3",
+            "-2.5e+2",
+            "18446744073709551615",
+            "18446744073709551616",
+            "-9223372036854775808",
+            "-9223372036854775809",
+            "123456789012345678901234567890",
+            "2.2250738585072014e-308",
+            r#""""#,
+            r#""abc""#,
+            r#""0123456789abcdef\"0123456789abcdef\\""#,
+            r#""ééééééééé中中中中中😀😀😀😀""#,
+            r#""\"\\\/\b\f\n\r\t""#,
+            r#""é中😀""#,
+            r#""\u00e9\u4E2D\ud83d\ude00""#,
+            "[]",
+            "[ ]",
+            "[1, [2, [3]], {}]",
+            "{}",
+            r#"{"a": {"b": [null, true, {"c": "d"}]}, "e": -1}"#,
+            "\n\t{\r\n\"a\" :\n1 }\n",
+        ] {
+            assert_same_as_serde_json(json);
+        }
+    }
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(from_str("-0").unwrap().as_u64(), None);
+        assert!(from_str("-0").unwrap().as_f64().unwrap().is_sign_negative());
+        assert_eq!(from_str("-1").unwrap().as_i64(), Some(-1));
+        assert_eq!(
+            from_str("18446744073709551615").unwrap().as_u64(),
+            Some(u64::MAX)
+        );
+        assert_eq!(
+            from_str("-9223372036854775808").unwrap().as_i64(),
+            Some(i64::MIN)
+        );
+        assert_eq!(from_str("18446744073709551616").unwrap().as_u64(), None);
+        assert_eq!(
+            from_str("18446744073709551616").unwrap().as_u128(),
+            Some(u64::MAX as u128 + 1)
+        );
+        assert_eq!(
+            from_str("-170141183460469231731687303715884105728")
+                .unwrap()
+                .as_i128(),
+            Some(i128::MIN)
+        );
+        assert_eq!(
+            from_str("-170141183460469231731687303715884105729")
+                .unwrap()
+                .as_f64(),
+            Some(-1.7014118346046923e38)
+        );
+        assert_eq!(from_str("1.0").unwrap().as_u64(), None);
+        assert_eq!(from_str("1.0").unwrap().as_f64(), Some(1.0));
+    }
+
+    #[cfg(feature = "arbitrary_precision")]
+    #[test]
+    fn test_arbitrary_precision() {
+        let json = r#"{"id":340282366920938463463374607431768211455,"big":340282366920938463463374607431768211456,"min":-170141183460469231731687303715884105728,"price":1.10,"zero":-0,"n":7,"huge":1E400}"#;
+        let value = from_str(json).unwrap();
+        let id = *value.get("id").clone().as_number_mut().unwrap();
+        assert_eq!(id.as_u128(), Some(u128::MAX));
+        assert_eq!(id.as_str(), None);
+        let big = *value.get("big").clone().as_number_mut().unwrap();
+        assert_eq!(
+            big.as_str(),
+            Some("340282366920938463463374607431768211456")
+        );
+        let min = *value.get("min").clone().as_number_mut().unwrap();
+        assert_eq!(min.as_i128(), Some(i128::MIN));
+        assert_eq!(min.as_u128(), None);
+        let price = *value.get("price").clone().as_number_mut().unwrap();
+        assert_eq!(price.as_str(), Some("1.10"));
+        assert_eq!(price.as_f64(), Some(1.1));
+        assert_eq!(price.as_u128(), None);
+        assert!(value.get("zero").as_f64().unwrap().is_sign_negative());
+        assert_eq!(value.get("n").as_u64(), Some(7));
+        let huge = *value.get("huge").clone().as_number_mut().unwrap();
+        assert_eq!(huge.as_str(), Some("1E400"));
+        assert_eq!(huge.as_f64(), None);
+
+        assert_eq!(serde_json::to_string(&value).unwrap(), json);
+        // Values which own their data don't keep the text.
+        let converted = crate::to_value(&value).unwrap();
+        let owned = value.clone().into_owned();
+        assert_eq!(converted, owned);
+        assert_eq!(owned.get("id"), value.get("id"));
+        assert_eq!(owned.get("price"), value.get("price"));
+        assert_eq!(owned.get("huge").as_f64(), Some(f64::MAX));
+        assert_eq!(serde_json::to_string(owned.get("price")).unwrap(), "1.1");
+    }
+
+    #[test]
+    fn test_borrowing() {
+        let value = from_str(r#"{"a": "b", "c\n": "d\te", "f": ["g"]}"#).unwrap();
+        let obj = value.as_object().unwrap();
+        let keys: Vec<Cow<str>> = obj.as_vec().iter().map(|(k, _)| k.clone().into()).collect();
+        assert!(matches!(keys[0], Cow::Borrowed("a")));
+        assert!(matches!(&keys[1], Cow::Owned(k) if k == "c\n"));
+        assert!(matches!(keys[2], Cow::Borrowed("f")));
+        assert!(matches!(value.get("a"), Value::Str(Cow::Borrowed("b"))));
+        assert!(matches!(value.get("c\n"), Value::Str(Cow::Owned(s)) if s == "d\te"));
+        assert!(matches!(
+            value.get("f").get(0),
+            Value::Str(Cow::Borrowed("g"))
+        ));
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        for (json, msg, line, column) in [
+            ("", "EOF while parsing a value", 1, 0),
+            ("  ", "EOF while parsing a value", 1, 2),
+            ("[1,]", "trailing comma", 1, 4),
+            ("[1 2]", "expected `,` or `]`", 1, 4),
+            ("[1", "EOF while parsing a list", 1, 2),
+            (r#"{"a" 1}"#, "expected `:`", 1, 6),
+            (r#"{"a": 1,}"#, "trailing comma", 1, 9),
+            (r#"{"a": 1"#, "EOF while parsing an object", 1, 7),
+            ("{1: 2}", "key must be a string", 1, 2),
+            ("\"abc", "EOF while parsing a string", 1, 4),
+            (
+                "\"a\nb\"",
+                "control character (\\u0000-\\u001F) found while parsing a string",
+                2,
+                0,
+            ),
+            (r#""\x""#, "invalid escape", 1, 3),
+            (r#""\u12g4""#, "invalid escape", 1, 6),
+            (r#""\ud83d""#, "lone leading surrogate in hex escape", 1, 8),
+            (r#""\ud83dA""#, "lone leading surrogate in hex escape", 1, 8),
+            ("tru", "EOF while parsing a value", 1, 3),
+            ("trux", "expected ident", 1, 4),
+            ("nul1", "expected ident", 1, 4),
+            ("x", "expected value", 1, 1),
+            ("-", "EOF while parsing a value", 1, 1),
+            ("-a", "invalid number", 1, 2),
+            ("1.", "EOF while parsing a value", 1, 2),
+            ("1.e3", "invalid number", 1, 3),
+            ("1e", "EOF while parsing a value", 1, 2),
+            ("01", "trailing characters", 1, 2),
+            ("{}\n\n {}", "trailing characters", 3, 2),
+            ("[\n  1,\n  x]", "expected value", 3, 3),
+        ] {
+            let err = from_str(json).unwrap_err();
+            assert_eq!(
+                (err.message(), err.line(), err.column()),
+                (msg, line, column),
+                "{:?}",
+                json
+            );
+        }
+        // With the `arbitrary_precision` feature flag, the text is kept instead.
+        #[cfg(not(feature = "arbitrary_precision"))]
+        {
+            let err = from_str("1e400").unwrap_err();
+            assert_eq!((err.message(), err.column()), ("number out of range", 5));
+        }
+    }
+
+    #[test]
+    fn test_syntax_errors_match_serde_json() {
+        for json in [
+            "[1,]",
+            "[1 2]",
+            "{\"a\" 1}",
+            "{1: 2}",
+            "\"a\nb\"",
+            "x",
+            "[\n  1,\n  x]",
+        ] {
+            let err = from_str(json).unwrap_err();
+            let expected = serde_json::from_str::<serde_json::Value>(json).unwrap_err();
+            assert_eq!(
+                (err.line(), err.column()),
+                (expected.line(), expected.column()),
+                "{:?}",
+                json
+            );
+        }
+    }
+
+    #[test]
+    fn test_recursion_limit() {
+        let ok = "[".repeat(128) + &"]".repeat(128);
+        assert!(from_str(&ok).is_ok());
+        let too_deep = "[".repeat(129) + &"]".repeat(129);
+        assert_eq!(
+            from_str(&too_deep).unwrap_err().message(),
+            "recursion limit exceeded"
+        );
+        let too_deep = r#"{"a":"#.repeat(129) + "null" + &"}".repeat(129);
+        assert_eq!(
+            from_str(&too_deep).unwrap_err().message(),
+            "recursion limit exceeded"
+        );
+    }
+
+    #[test]
+    fn test_from_slice() {
+        let value = from_slice(br#"{"a": "b"}"#).unwrap();
+        assert_eq!(value.get("a"), &Value::Str("b".into()));
+        let err = from_slice(b"[\"a\",\n\"\xff\"]").unwrap_err();
+        assert_eq!(
+            (err.message(), err.line(), err.column()),
+            ("invalid UTF-8", 2, 2)
+        );
+    }
+
+    #[test]
+    fn test_duplicate_key_policy() {
+        let json = r#"{"a": 1, "b": {"c": 2, "c": 3}, "a": 4}"#;
+        let value = from_str(json).unwrap();
+        assert_eq!(value.as_object().unwrap().len(), 3);
+        for (policy, expected) in [
+            (DuplicateKeyPolicy::KeepFirst, r#"{"a": 1, "b": {"c": 2}}"#),
+            (DuplicateKeyPolicy::KeepLast, r#"{"a": 4, "b": {"c": 3}}"#),
+        ] {
+            let value = from_str_with_policy(json, policy).unwrap();
+            let expected: serde_json::Value = serde_json::from_str(expected).unwrap();
+            assert_eq!(serde_json::Value::from(value), expected);
+        }
+        let err = from_slice_with_policy(json.as_bytes(), DuplicateKeyPolicy::Error).unwrap_err();
+        assert_eq!((err.message(), err.column()), ("duplicate key `c`", 30));
+
+        let large = (0..100)
+            .chain(0..100)
+            .map(|i| format!("\"k{}\": {}", i % 100, i))
+            .collect::<Vec<_>>()
+            .join(",");
+        let large = format!("{{{}}}", large);
+        let value = from_str_with_policy(&large, DuplicateKeyPolicy::KeepLast);
+        assert_eq!(value.unwrap().as_object().unwrap().len(), 100);
+    }
+}
@@ -1,9 +1,17 @@
-use serde::ser::{Serialize, Serializer};
+use std::borrow::Cow;
 
+use serde::ser::{self, Impossible, Serialize, Serializer};
+use serde_json::value::RawValue;
+
+use crate::cowstr::CowStr;
+use crate::error::Error;
 use crate::num::{Number, N};
 use crate::ownedvalue::OwnedValue;
 use crate::value::Value;
-use crate::Map;
+use crate::{Map, ObjectAsVec};
+
+/// The name serde_json uses to serialize a [`RawValue`] as struct.
+const RAW_VALUE_TOKEN: &str = "$serde_json::private::RawValue";
 
 impl Serialize for Value<'_> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -29,7 +37,7 @@ impl Serialize for Map<'_> {
     }
 }
 
-impl Serialize for OwnedValue {
+impl<B> Serialize for OwnedValue<B> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
@@ -38,29 +46,711 @@ impl Serialize for OwnedValue {
     }
 }
 
-impl Serialize for Number {
+/// Numbers stored as text are serialized verbatim as a [`RawValue`].
+impl Serialize for Number<'_> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        match self.n {
-            N::PosInt(n) => serializer.serialize_u64(n),
-            N::NegInt(n) => serializer.serialize_i64(n),
-            N::Float(n) => serializer.serialize_f64(n),
+        match &self.n {
+            N::PosInt(n) => serializer.serialize_u64(*n),
+            N::NegInt(n) => serializer.serialize_i64(*n),
+            N::PosInt128(n) => serializer.serialize_u128(n.get()),
+            N::NegInt128(n) => serializer.serialize_i128(n.get() as i128),
+            N::Float(n) => serializer.serialize_f64(*n),
+            N::FloatLexeme(_, s) | N::BigInt(_, s) => {
+                let raw: &RawValue = serde_json::from_str(s).map_err(ser::Error::custom)?;
+                raw.serialize(serializer)
+            }
         }
     }
 }
 
+/// Converts a `T` into a [`Value`], which owns all its data.
+///
+/// Objects are built directly as [`ObjectAsVec`], preserving the order of the fields. Numbers
+/// don't keep their text, like with [`Number::into_owned`].
+///
+/// # Example
+/// ```
+/// use serde::Serialize;
+/// use serde_json_borrow::Value;
+///
+/// #[derive(Serialize)]
+/// struct User {
+///     name: String,
+///     age: u64,
+/// }
+///
+/// let user = User {
+///     name: "John".to_string(),
+///     age: 30,
+/// };
+/// let value = serde_json_borrow::to_value(&user).unwrap();
+/// assert_eq!(value.get("name"), &Value::Str("John".into()));
+/// assert_eq!(value.get("age"), &Value::Number(30_u64.into()));
+/// ```
+///
+/// # Errors
+/// Fails if `T`'s implementation of `Serialize` decides to fail, or if `T` contains a map with
+/// non-string keys.
+pub fn to_value<T>(value: &T) -> Result<Value<'static>, Error>
+where T: ?Sized + Serialize {
+    value.serialize(ValueSerializer)
+}
+
+/// Serializer whose output is a `Value<'static>`.
+struct ValueSerializer;
+
+impl Serializer for ValueSerializer {
+    type Ok = Value<'static>;
+    type Error = Error;
+
+    type SerializeSeq = SerializeVec;
+    type SerializeTuple = SerializeVec;
+    type SerializeTupleStruct = SerializeVec;
+    type SerializeTupleVariant = SerializeTupleVariant;
+    type SerializeMap = SerializeMap;
+    type SerializeStruct = SerializeMap;
+    type SerializeStructVariant = SerializeStructVariant;
+
+    #[inline]
+    fn serialize_bool(self, value: bool) -> Result<Value<'static>, Error> {
+        Ok(Value::Bool(value))
+    }
+
+    #[inline]
+    fn serialize_i8(self, value: i8) -> Result<Value<'static>, Error> {
+        self.serialize_i64(value as i64)
+    }
+
+    #[inline]
+    fn serialize_i16(self, value: i16) -> Result<Value<'static>, Error> {
+        self.serialize_i64(value as i64)
+    }
+
+    #[inline]
+    fn serialize_i32(self, value: i32) -> Result<Value<'static>, Error> {
+        self.serialize_i64(value as i64)
+    }
+
+    #[inline]
+    fn serialize_i64(self, value: i64) -> Result<Value<'static>, Error> {
+        Ok(Value::Number(value.into()))
+    }
+
+    #[inline]
+    fn serialize_i128(self, value: i128) -> Result<Value<'static>, Error> {
+        Ok(Value::Number(value.into()))
+    }
+
+    #[inline]
+    fn serialize_u8(self, value: u8) -> Result<Value<'static>, Error> {
+        self.serialize_u64(value as u64)
+    }
+
+    #[inline]
+    fn serialize_u16(self, value: u16) -> Result<Value<'static>, Error> {
+        self.serialize_u64(value as u64)
+    }
+
+    #[inline]
+    fn serialize_u32(self, value: u32) -> Result<Value<'static>, Error> {
+        self.serialize_u64(value as u64)
+    }
+
+    #[inline]
+    fn serialize_u64(self, value: u64) -> Result<Value<'static>, Error> {
+        Ok(Value::Number(value.into()))
+    }
+
+    #[inline]
+    fn serialize_u128(self, value: u128) -> Result<Value<'static>, Error> {
+        Ok(Value::Number(value.into()))
+    }
+
+    #[inline]
+    fn serialize_f32(self, value: f32) -> Result<Value<'static>, Error> {
+        self.serialize_f64(value as f64)
+    }
+
+    #[inline]
+    fn serialize_f64(self, value: f64) -> Result<Value<'static>, Error> {
+        // Like serde_json, NaN and infinity are serialized as null
+        Ok(Value::from(value))
+    }
+
+    #[inline]
+    fn serialize_char(self, value: char) -> Result<Value<'static>, Error> {
+        Ok(Value::Str(Cow::Owned(value.to_string())))
+    }
+
+    #[inline]
+    fn serialize_str(self, value: &str) -> Result<Value<'static>, Error> {
+        Ok(Value::Str(Cow::Owned(value.to_owned())))
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<Value<'static>, Error> {
+        let vec = value
+            .iter()
+            .map(|&b| Value::Number((b as u64).into()))
+            .collect();
+        Ok(Value::Array(vec))
+    }
+
+    #[inline]
+    fn serialize_unit(self) -> Result<Value<'static>, Error> {
+        Ok(Value::Null)
+    }
+
+    #[inline]
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value<'static>, Error> {
+        self.serialize_unit()
+    }
+
+    #[inline]
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Value<'static>, Error> {
+        Ok(Value::Str(Cow::Borrowed(variant)))
+    }
+
+    #[inline]
+    fn serialize_newtype_struct<T>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Value<'static>, Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Value<'static>, Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let value = value.serialize(ValueSerializer)?;
+        Ok(Value::Object(ObjectAsVec::from_entries(vec![(
+            variant.into(),
+            value,
+        )])))
+    }
+
+    #[inline]
+    fn serialize_none(self) -> Result<Value<'static>, Error> {
+        self.serialize_unit()
+    }
+
+    #[inline]
+    fn serialize_some<T>(self, value: &T) -> Result<Value<'static>, Error>
+    where T: ?Sized + Serialize {
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Ok(SerializeVec {
+            vec: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Ok(SerializeTupleVariant {
+            variant,
+            vec: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Ok(SerializeMap {
+            entries: Vec::with_capacity(len.unwrap_or(0)),
+            next_key: None,
+            raw_value: false,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        let mut map = self.serialize_map(Some(len))?;
+        map.raw_value = name == RAW_VALUE_TOKEN;
+        Ok(map)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Ok(SerializeStructVariant {
+            variant,
+            entries: Vec::with_capacity(len),
+        })
+    }
+}
+
+struct SerializeVec {
+    vec: Vec<Value<'static>>,
+}
+
+struct SerializeTupleVariant {
+    variant: &'static str,
+    vec: Vec<Value<'static>>,
+}
+
+struct SerializeMap {
+    entries: Vec<(CowStr<'static>, Value<'static>)>,
+    next_key: Option<CowStr<'static>>,
+    /// Whether the struct is a [`RawValue`], whose JSON text is parsed into the value.
+    raw_value: bool,
+}
+
+struct SerializeStructVariant {
+    variant: &'static str,
+    entries: Vec<(CowStr<'static>, Value<'static>)>,
+}
+
+impl ser::SerializeSeq for SerializeVec {
+    type Ok = Value<'static>;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where T: ?Sized + Serialize {
+        self.vec.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value<'static>, Error> {
+        Ok(Value::Array(self.vec))
+    }
+}
+
+impl ser::SerializeTuple for SerializeVec {
+    type Ok = Value<'static>;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where T: ?Sized + Serialize {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value<'static>, Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeVec {
+    type Ok = Value<'static>;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where T: ?Sized + Serialize {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value<'static>, Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeTupleVariant {
+    type Ok = Value<'static>;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where T: ?Sized + Serialize {
+        self.vec.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value<'static>, Error> {
+        Ok(Value::Object(ObjectAsVec::from_entries(vec![(
+            self.variant.into(),
+            Value::Array(self.vec),
+        )])))
+    }
+}
+
+impl ser::SerializeMap for SerializeMap {
+    type Ok = Value<'static>;
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Error>
+    where T: ?Sized + Serialize {
+        self.next_key = Some(key.serialize(MapKeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Error>
+    where T: ?Sized + Serialize {
+        let key = self
+            .next_key
+            .take()
+            .expect("serialize_value called before serialize_key");
+        self.entries.push((key, value.serialize(ValueSerializer)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value<'static>, Error> {
+        Ok(Value::Object(ObjectAsVec::from_entries(self.entries)))
+    }
+}
+
+impl ser::SerializeStruct for SerializeMap {
+    type Ok = Value<'static>;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where T: ?Sized + Serialize {
+        self.entries
+            .push((key.into(), value.serialize(ValueSerializer)?));
+        Ok(())
+    }
+
+    fn end(mut self) -> Result<Value<'static>, Error> {
+        if self.raw_value {
+            if let Some((_, Value::Str(json))) = self.entries.pop() {
+                return crate::from_str(&json).map(Value::into_owned);
+            }
+        }
+        ser::SerializeMap::end(self)
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeStructVariant {
+    type Ok = Value<'static>;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where T: ?Sized + Serialize {
+        self.entries
+            .push((key.into(), value.serialize(ValueSerializer)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value<'static>, Error> {
+        Ok(Value::Object(ObjectAsVec::from_entries(vec![(
+            self.variant.into(),
+            Value::Object(ObjectAsVec::from_entries(self.entries)),
+        )])))
+    }
+}
+
+fn key_must_be_a_string() -> Error {
+    ser::Error::custom("key must be a string")
+}
+
+/// Serializer for object keys. Like serde_json, integer and bool keys are converted to
+/// strings.
+struct MapKeySerializer;
+
+impl Serializer for MapKeySerializer {
+    type Ok = CowStr<'static>;
+    type Error = Error;
+
+    type SerializeSeq = Impossible<CowStr<'static>, Error>;
+    type SerializeTuple = Impossible<CowStr<'static>, Error>;
+    type SerializeTupleStruct = Impossible<CowStr<'static>, Error>;
+    type SerializeTupleVariant = Impossible<CowStr<'static>, Error>;
+    type SerializeMap = Impossible<CowStr<'static>, Error>;
+    type SerializeStruct = Impossible<CowStr<'static>, Error>;
+    type SerializeStructVariant = Impossible<CowStr<'static>, Error>;
+
+    #[inline]
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<CowStr<'static>, Error> {
+        Ok(variant.into())
+    }
+
+    #[inline]
+    fn serialize_newtype_struct<T>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<CowStr<'static>, Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_bool(self, value: bool) -> Result<CowStr<'static>, Error> {
+        Ok(if value { "true" } else { "false" }.into())
+    }
+
+    fn serialize_i8(self, value: i8) -> Result<CowStr<'static>, Error> {
+        Ok(value.to_string().into())
+    }
+
+    fn serialize_i16(self, value: i16) -> Result<CowStr<'static>, Error> {
+        Ok(value.to_string().into())
+    }
+
+    fn serialize_i32(self, value: i32) -> Result<CowStr<'static>, Error> {
+        Ok(value.to_string().into())
+    }
+
+    fn serialize_i64(self, value: i64) -> Result<CowStr<'static>, Error> {
+        Ok(value.to_string().into())
+    }
+
+    fn serialize_i128(self, value: i128) -> Result<CowStr<'static>, Error> {
+        Ok(value.to_string().into())
+    }
+
+    fn serialize_u8(self, value: u8) -> Result<CowStr<'static>, Error> {
+        Ok(value.to_string().into())
+    }
+
+    fn serialize_u16(self, value: u16) -> Result<CowStr<'static>, Error> {
+        Ok(value.to_string().into())
+    }
+
+    fn serialize_u32(self, value: u32) -> Result<CowStr<'static>, Error> {
+        Ok(value.to_string().into())
+    }
+
+    fn serialize_u64(self, value: u64) -> Result<CowStr<'static>, Error> {
+        Ok(value.to_string().into())
+    }
+
+    fn serialize_u128(self, value: u128) -> Result<CowStr<'static>, Error> {
+        Ok(value.to_string().into())
+    }
+
+    fn serialize_f32(self, _value: f32) -> Result<CowStr<'static>, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_f64(self, _value: f64) -> Result<CowStr<'static>, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    #[inline]
+    fn serialize_char(self, value: char) -> Result<CowStr<'static>, Error> {
+        Ok(value.to_string().into())
+    }
+
+    #[inline]
+    fn serialize_str(self, value: &str) -> Result<CowStr<'static>, Error> {
+        Ok(value.to_owned().into())
+    }
+
+    fn serialize_bytes(self, _value: &[u8]) -> Result<CowStr<'static>, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit(self) -> Result<CowStr<'static>, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<CowStr<'static>, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<CowStr<'static>, Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_none(self) -> Result<CowStr<'static>, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_some<T>(self, _value: &T) -> Result<CowStr<'static>, Error>
+    where T: ?Sized + Serialize {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(key_must_be_a_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
+
+    use serde::Serialize;
+
+    use crate::{to_value, Value};
 
     #[test]
     fn serialize_json_test() {
         let json_obj =
             r#"{"bool":true,"string_key":"string_val","float":1.23,"i64":-123,"u64":123}"#;
 
-        let val1: crate::Value = serde_json::from_str(json_obj).unwrap();
+        let val1: Value = serde_json::from_str(json_obj).unwrap();
         let deser1: String = serde_json::to_string(&val1).unwrap();
         assert_eq!(deser1, json_obj);
     }
+
+    #[derive(Serialize)]
+    enum Enum {
+        Unit,
+        Newtype(u64),
+        Tuple(u64, bool),
+        Struct { b: u64, a: Option<u64> },
+    }
+
+    #[derive(Serialize)]
+    struct Record {
+        name: String,
+        score: f64,
+        neg: i32,
+        tags: Vec<&'static str>,
+        nested: BTreeMap<u32, Enum>,
+        skipped: Option<()>,
+    }
+
+    #[test]
+    fn to_value_matches_serde_json() {
+        let mut nested = BTreeMap::new();
+        nested.insert(3, Enum::Unit);
+        nested.insert(1, Enum::Newtype(5));
+        nested.insert(2, Enum::Tuple(1, true));
+        nested.insert(4, Enum::Struct { b: 1, a: None });
+        let record = Record {
+            name: "escaped \"name\"".to_string(),
+            score: 1.5,
+            neg: -3,
+            tags: vec!["a", "b"],
+            nested,
+            skipped: None,
+        };
+
+        let value = to_value(&record).unwrap();
+        let expected = serde_json::to_value(&record).unwrap();
+        assert_eq!(serde_json::Value::from(&value), expected);
+        assert_eq!(value.get("neg"), &Value::Number((-3i64).into()));
+        assert_eq!(
+            value.get("nested").get("1").get("Newtype"),
+            &Value::from(5u64)
+        );
+    }
+
+    #[test]
+    fn to_value_preserves_field_order() {
+        let value = to_value(&Enum::Struct { b: 1, a: Some(2) }).unwrap();
+        let keys: Vec<_> = value.get("Struct").as_object().unwrap().keys().collect();
+        assert_eq!(keys, vec!["b", "a"]);
+    }
+
+    #[test]
+    fn to_value_roundtrip() {
+        let json_obj = r#"{"bool":true,"string_key":"string\"_val","float":1.23,"i64":-123,"u64":123,"arr":[null,{}]}"#;
+        let val: Value = serde_json::from_str(json_obj).unwrap();
+        assert_eq!(to_value(&val).unwrap(), val);
+    }
+
+    #[test]
+    fn to_value_int128() {
+        assert_eq!(to_value(&u128::MAX).unwrap().as_u128(), Some(u128::MAX));
+        assert_eq!(to_value(&i128::MIN).unwrap().as_i128(), Some(i128::MIN));
+        assert_eq!(to_value(&5u128).unwrap(), Value::from(5u64));
+        let value = Value::from(i128::MIN);
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            i128::MIN.to_string()
+        );
+    }
+
+    #[test]
+    fn to_value_errors() {
+        let mut map = BTreeMap::new();
+        map.insert(vec![1], 1);
+        assert_eq!(
+            to_value(&map).unwrap_err().to_string(),
+            "key must be a string"
+        );
+        assert_eq!(to_value(&f64::NAN).unwrap(), Value::Null);
+    }
 }
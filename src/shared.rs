@@ -0,0 +1,148 @@
+use std::fmt;
+use std::ptr::NonNull;
+use std::sync::Arc;
+
+use crate::index::Index;
+use crate::{OwnedValue, Value};
+
+/// A reference counted handle to an [`OwnedValue`], or to a value nested inside of it.
+///
+/// Cloning a `SharedValue` is O(1), it only increments the reference count. Handles to nested
+/// values are created with [`SharedValue::project`], and keep the whole document alive.
+///
+/// ## Example
+/// ```
+/// use serde_json_borrow::{OwnedValue, SharedValue, Value};
+///
+/// let raw_json = r#"{"id": 1, "payload": {"name": "John"}}"#.to_string();
+/// let shared = SharedValue::from(OwnedValue::from_string(raw_json).unwrap());
+/// let payload = shared.project("payload").unwrap();
+/// drop(shared);
+/// let sinks = vec![payload.clone(), payload.clone()];
+/// for sink in sinks {
+///     assert_eq!(sink.get_value().get("name"), &Value::Str("John".into()));
+/// }
+/// ```
+pub struct SharedValue<B = String> {
+    root: Arc<OwnedValue<B>>,
+    /// Points into the value of `root`, which is never mutated.
+    value: NonNull<Value<'static>>,
+}
+
+// SAFETY: `value` points into `root`, so the handle is equivalent to an `Arc<OwnedValue<B>>`.
+unsafe impl<B: Send + Sync> Send for SharedValue<B> {}
+// SAFETY: See above.
+unsafe impl<B: Send + Sync> Sync for SharedValue<B> {}
+
+impl<B> SharedValue<B> {
+    /// Wraps `value` into a reference counted handle.
+    pub fn new(value: OwnedValue<B>) -> Self {
+        let root = Arc::new(value);
+        let value = NonNull::from(&**root);
+        SharedValue { root, value }
+    }
+
+    /// Returns a handle to the value at `index`, sharing the document with `self`, or `None` if
+    /// the key or index does not exist, like [`Value::get_mut`].
+    ///
+    /// ```
+    /// # use serde_json_borrow::{OwnedValue, SharedValue, Value};
+    /// let raw_json = r#"{"items": [{"id": 1}, {"id": 2}]}"#.to_string();
+    /// let shared = SharedValue::from(OwnedValue::from_string(raw_json).unwrap());
+    /// let item = shared.project("items").and_then(|items| items.project(1)).unwrap();
+    /// assert_eq!(item.get_value().get("id"), &Value::from(2u64));
+    /// assert!(shared.project("missing").is_none());
+    /// ```
+    pub fn project<I: Index>(&self, index: I) -> Option<Self> {
+        let value = index.index_into(self.get_value())?;
+        Some(SharedValue {
+            root: self.root.clone(),
+            // The nested value is part of `root` as well.
+            value: NonNull::from(value).cast(),
+        })
+    }
+
+    /// Returns the document this handle is part of.
+    pub fn root(&self) -> &OwnedValue<B> {
+        &self.root
+    }
+
+    /// Returns the `Value` reference.
+    ///
+    /// The value borrows from the handle, so no part of it can outlive the handle:
+    /// ```compile_fail
+    /// # use serde_json_borrow::{OwnedValue, SharedValue};
+    /// let shared = SharedValue::from(OwnedValue::from_str(r#""text""#).unwrap());
+    /// let text: &'static str = shared.get_value().as_str().unwrap();
+    /// ```
+    pub fn get_value(&self) -> &Value<'_> {
+        // SAFETY: `value` points into `root`, which is kept alive and never mutated. The lifetime
+        // of the strings is shortened to the lifetime of `self`.
+        unsafe { self.value.as_ref() }
+    }
+}
+
+impl<B> From<OwnedValue<B>> for SharedValue<B> {
+    fn from(value: OwnedValue<B>) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<B> Clone for SharedValue<B> {
+    fn clone(&self) -> Self {
+        SharedValue {
+            root: self.root.clone(),
+            value: self.value,
+        }
+    }
+}
+
+impl<B> fmt::Debug for SharedValue<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.get_value(), f)
+    }
+}
+
+impl<B> PartialEq for SharedValue<B> {
+    fn eq(&self, other: &Self) -> bool {
+        self.get_value() == other.get_value()
+    }
+}
+
+impl<B> Eq for SharedValue<B> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project() {
+        let raw_json = r#"{"a": {"b": ["c", {"d": "e"}]}, "f": null}"#;
+        let shared = SharedValue::from(OwnedValue::from_str(raw_json).unwrap());
+        let b = shared.project("a").unwrap().project("b").unwrap();
+        let d = b.project(1).unwrap().project("d").unwrap();
+        drop(shared);
+        assert_eq!(b.get_value().get(0), &Value::Str("c".into()));
+        assert_eq!(d.get_value().as_str(), Some("e"));
+        assert_eq!(d.root().get("f"), &Value::Null);
+        assert!(b.project("a").is_none());
+        assert!(b.project(2).is_none());
+    }
+
+    #[test]
+    fn test_clone_shares_document() {
+        let shared = SharedValue::from(OwnedValue::from_str(r#"{"a": [1]}"#).unwrap());
+        let a = shared.project("a").unwrap();
+        let clone = a.clone();
+        assert!(std::ptr::eq(a.get_value(), clone.get_value()));
+        assert!(std::ptr::eq(a.root(), shared.root()));
+        assert_eq!(Arc::strong_count(&shared.root), 3);
+        assert_eq!(clone, a);
+        assert_ne!(clone, shared);
+
+        let handle = std::thread::spawn(move || clone.get_value().get(0).as_u64())
+            .join()
+            .unwrap();
+        assert_eq!(handle, Some(1));
+    }
+}
@@ -0,0 +1,320 @@
+use simd_json::{Node, StaticNode};
+
+use crate::object_vec::ObjectAsVec;
+use crate::parser::RECURSION_LIMIT;
+use crate::{Error, Value};
+
+/// Parses JSON from a mutable buffer into a [`Value`] with SIMD acceleration, borrowing all
+/// strings and keys from the buffer.
+///
+/// This requires the `simd` feature flag. The structure of the document is found with
+/// [simd-json](https://github.com/simd-lite/simd-json), if the CPU supports the required
+/// instructions (AVX2 or SSE4.2 on x86, NEON on aarch64, or `simd128` on wasm). Otherwise this
+/// falls back to the scalar parser of [`from_slice`](crate::from_slice).
+///
+/// The result is always the same as the one of [`from_slice`](crate::from_slice). Invalid JSON,
+/// numbers which simd-json can't represent, like integers which don't fit into 64 bits, and the
+/// integer `-0` are handed over to the scalar parser, so errors and numbers are reported the same
+/// way. With the `arbitrary_precision` feature flag, this also applies to documents containing
+/// floats.
+///
+/// Escape sequences in strings are unescaped in place, which is why the buffer needs to be
+/// mutable. Therefore even strings and keys with escape sequences are borrowed. The contents of
+/// the buffer are unspecified after parsing.
+///
+/// Nesting is limited to a depth of 128, and duplicate keys in objects are kept, like in
+/// [`from_str`](crate::from_str).
+///
+/// This allocates scratch buffers for every call, which is expensive for small documents. Use
+/// [`SimdParser`] to reuse them when parsing many documents.
+///
+/// ## Performance
+/// SIMD pays off for larger documents and long strings. For small documents, like typical lines
+/// of NDJSON, the fixed overhead per document can make this slower than
+/// [`from_str`](crate::from_str), so benchmark with your data.
+///
+/// ## Example
+/// ```
+/// use serde_json_borrow::Value;
+///
+/// let mut data = br#"{"name": "John", "tag\"": [1, 2.5, null]}"#.to_vec();
+/// let value = serde_json_borrow::from_slice_simd(&mut data).unwrap();
+/// assert_eq!(value.get("name"), &Value::Str("John".into()));
+/// assert_eq!(value.get("tag\"").get(1), &Value::from(2.5));
+/// ```
+pub fn from_slice_simd(json: &mut [u8]) -> Result<Value<'_>, Error> {
+    SimdParser::new().parse(json)
+}
+
+/// A SIMD accelerated parser, which keeps its scratch buffers between documents.
+///
+/// This requires the `simd` feature flag. See [`from_slice_simd`] for details.
+///
+/// ## Example
+/// ```
+/// use serde_json_borrow::SimdParser;
+///
+/// let mut parser = SimdParser::new();
+/// for line in [r#"{"id": 1}"#, r#"{"id": 2}"#] {
+///     let mut data = line.as_bytes().to_vec();
+///     let value = parser.parse(&mut data).unwrap();
+///     assert!(value.get("id").as_u64().is_some());
+/// }
+/// ```
+#[derive(Default)]
+pub struct SimdParser {
+    buffers: simd_json::Buffers,
+    /// Copy of an input with escape sequences, to restore it for the scalar parser after
+    /// simd-json unescaped strings in place.
+    input: Vec<u8>,
+}
+
+impl SimdParser {
+    /// Creates a parser with empty scratch buffers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses JSON from a mutable buffer into a [`Value`], borrowing all strings and keys from
+    /// the buffer. See [`from_slice_simd`].
+    pub fn parse<'ctx>(&mut self, json: &'ctx mut [u8]) -> Result<Value<'ctx>, Error> {
+        if !simd_supported() || may_contain_negative_zero(json) {
+            return crate::from_slice(json);
+        }
+        // Only strings with escape sequences are written to by simd-json.
+        let escaped = json.contains(&b'\\');
+        if escaped {
+            self.input.clear();
+            self.input.extend_from_slice(json);
+        }
+        let (ptr, len) = (json.as_mut_ptr(), json.len());
+        {
+            // SAFETY: `ptr` and `len` describe `json`, which is not accessed while this slice or
+            // anything borrowed from it is alive. The borrow checker can't see this, since the
+            // value is returned in one branch, so it would extend the borrow to all branches.
+            let json: &'ctx mut [u8] = unsafe { std::slice::from_raw_parts_mut(ptr, len) };
+            if let Ok(tape) = simd_json::to_tape_with_buffers(json, &mut self.buffers) {
+                // The tape is produced by simd-json from valid JSON, so it always contains a
+                // complete value.
+                if let Some(value) = convert(&tape.0, &mut 0, RECURSION_LIMIT) {
+                    return Ok(value);
+                }
+            }
+        }
+        if escaped {
+            json.copy_from_slice(&self.input);
+        }
+        crate::from_slice(json)
+    }
+}
+
+/// Whether the JSON may contain the integer `-0`, which simd-json parses as `0` instead of
+/// `-0.0`. Matches inside of strings are possible, which only cost the SIMD acceleration.
+fn may_contain_negative_zero(json: &[u8]) -> bool {
+    let mut rest = json;
+    while let Some(pos) = rest.iter().position(|&b| b == b'-') {
+        rest = &rest[pos + 1..];
+        if rest.first() == Some(&b'0')
+            && matches!(
+                rest.get(1),
+                None | Some(b' ' | b'\t' | b'\n' | b'\r' | b',' | b']' | b'}')
+            )
+        {
+            return true;
+        }
+    }
+    false
+}
+
+/// Whether the CPU supports one of the SIMD implementations of simd-json.
+fn simd_supported() -> bool {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        std::is_x86_feature_detected!("avx2") || std::is_x86_feature_detected!("sse4.2")
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        true
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        cfg!(target_feature = "simd128")
+    }
+    #[cfg(not(any(
+        target_arch = "x86",
+        target_arch = "x86_64",
+        target_arch = "aarch64",
+        target_arch = "wasm32"
+    )))]
+    {
+        false
+    }
+}
+
+/// Converts the value at `pos` on the tape, and advances `pos` past it.
+///
+/// Returns `None` if the value has to be parsed by the scalar parser, which is the case if the
+/// recursion limit is exceeded, and for floats with the `arbitrary_precision` feature flag.
+fn convert<'ctx>(
+    nodes: &[Node<'ctx>],
+    pos: &mut usize,
+    remaining_depth: u8,
+) -> Option<Value<'ctx>> {
+    let node = nodes[*pos];
+    *pos += 1;
+    Some(match node {
+        Node::String(s) => Value::Str(s.into()),
+        Node::Static(StaticNode::Null) => Value::Null,
+        Node::Static(StaticNode::Bool(b)) => Value::Bool(b),
+        Node::Static(StaticNode::I64(n)) => Value::Number(n.into()),
+        Node::Static(StaticNode::U64(n)) => Value::Number(n.into()),
+        #[cfg(feature = "arbitrary_precision")]
+        Node::Static(StaticNode::F64(_)) => return None,
+        #[cfg(not(feature = "arbitrary_precision"))]
+        Node::Static(StaticNode::F64(n)) => Value::from(n),
+        Node::Array { len, .. } => {
+            let remaining_depth = remaining_depth.checked_sub(1)?;
+            let mut values = Vec::with_capacity(len);
+            for _ in 0..len {
+                values.push(convert(nodes, pos, remaining_depth)?);
+            }
+            Value::Array(values)
+        }
+        Node::Object { len, .. } => {
+            let remaining_depth = remaining_depth.checked_sub(1)?;
+            let mut entries = Vec::with_capacity(len);
+            for _ in 0..len {
+                let Node::String(key) = nodes[*pos] else {
+                    unreachable!("object keys are strings");
+                };
+                *pos += 1;
+                entries.push((key.into(), convert(nodes, pos, remaining_depth)?));
+            }
+            Value::Object(ObjectAsVec::from_entries(entries))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::*;
+
+    fn assert_same_as_scalar(json: &str) {
+        let mut data = json.as_bytes().to_vec();
+        match (from_slice_simd(&mut data), crate::from_str(json)) {
+            (Ok(value), Ok(expected)) => {
+                assert_eq!(value, expected, "{}", json);
+                assert_eq!(
+                    format!("{:?}", value),
+                    format!("{:?}", expected),
+                    "{}",
+                    json
+                );
+            }
+            (Err(err), Err(expected)) => {
+                assert_eq!(err.message(), expected.message(), "{}", json);
+                assert_eq!(err.line(), expected.line(), "{}", json);
+                assert_eq!(err.column(), expected.column(), "{}", json);
+            }
+            (result, expected) => panic!("{}: {:?} != {:?}", json, result, expected),
+        }
+    }
+
+    #[test]
+    fn test_same_as_scalar() {
+        for json in [
+            "null",
+            "true",
+            "-1",
+            "18446744073709551615",
+            "1.5e3",
+            r#""abc""#,
+            r#""a\"b\\c😀""#,
+            "[]",
+            "{}",
+            r#"{"a": {"b": [null, true, {"c": "d"}]}, "e\n": -1, "f": []}"#,
+        ] {
+            assert_same_as_scalar(json);
+        }
+    }
+
+    #[test]
+    fn test_numbers_same_as_scalar() {
+        for json in [
+            "0",
+            "-0",
+            "[-0, 0]",
+            r#"{"a": -0}"#,
+            r#"["2024-01-05", -0.5, -0.0]"#,
+            "9223372036854775807",
+            "-9223372036854775808",
+            "18446744073709551616",
+            "-9223372036854775809",
+            "170141183460469231731687303715884105727",
+            "-170141183460469231731687303715884105728",
+            "340282366920938463463374607431768211456",
+            "1.10000000000000000001",
+            "1e400",
+            r#"["a\"b", 18446744073709551616, "c\nd"]"#,
+        ] {
+            assert_same_as_scalar(json);
+        }
+    }
+
+    #[test]
+    fn test_errors_same_as_scalar() {
+        let deep = "[".repeat(129) + &"]".repeat(129);
+        for json in [
+            "",
+            "[1,\n 2,]",
+            "[1,\n 1e400]",
+            r#"{"a" 1}"#,
+            "tru",
+            r#"["a\nb", "c\"d", 1e400]"#,
+            r#"["a\nb",\n "c\"d" x]"#,
+            r#""\x""#,
+            &deep,
+        ] {
+            assert_same_as_scalar(json);
+        }
+    }
+
+    #[test]
+    fn test_escaped_strings_are_borrowed() {
+        let mut data = br#"{"a\"": "b\nc"}"#.to_vec();
+        let value = from_slice_simd(&mut data).unwrap();
+        if simd_supported() {
+            let (key, val) = &value.as_object().unwrap().as_vec()[0];
+            assert!(matches!(key.0, Cow::Borrowed("a\"")));
+            assert!(matches!(val, Value::Str(Cow::Borrowed("b\nc"))));
+        }
+        assert_eq!(value.get("a\""), &Value::Str("b\nc".into()));
+    }
+
+    #[test]
+    fn test_parser_reuse() {
+        let mut parser = SimdParser::new();
+        let mut long = format!(r#"{{"a": "{}"}}"#, "x".repeat(1000)).into_bytes();
+        assert_eq!(
+            parser
+                .parse(&mut long)
+                .unwrap()
+                .get("a")
+                .as_str()
+                .unwrap()
+                .len(),
+            1000
+        );
+        let mut data = br#"{"a": [1]}"#.to_vec();
+        assert_eq!(
+            parser.parse(&mut data).unwrap().get("a").get(0),
+            &Value::from(1u64)
+        );
+        let mut data = b"[".to_vec();
+        assert!(parser.parse(&mut data).is_err());
+        let mut data = b"[]".to_vec();
+        assert_eq!(parser.parse(&mut data).unwrap(), Value::Array(Vec::new()));
+    }
+}
@@ -0,0 +1,158 @@
+use std::ops::Range;
+
+use crate::parser::Parser;
+use crate::{DuplicateKeyPolicy, Error, Value};
+
+impl<'ctx> Value<'ctx> {
+    /// Parses a buffer containing a sequence of JSON values, e.g. `{"a":1}{"b":2} [3]`, and
+    /// returns an iterator over the values, borrowing from the buffer.
+    ///
+    /// Each value is returned with the byte range it occupies in the buffer, which can be used to
+    /// re-slice the raw JSON text of the value. Values can be separated by whitespace. Numbers,
+    /// `true`, `false` and `null` need to be separated by whitespace from a following value.
+    ///
+    /// Values are parsed with [`from_str`](crate::from_str), so duplicate keys in objects are
+    /// kept unless another policy is set with [`ValueStream::with_policy`]. The iterator ends
+    /// after the first error.
+    ///
+    /// ## Example
+    /// ```
+    /// use serde_json_borrow::Value;
+    ///
+    /// let data = r#"{"a":1}{"b":2} [3]"#;
+    /// let values = Value::stream_from_str(data).collect::<Result<Vec<_>, _>>().unwrap();
+    /// assert_eq!(values.len(), 3);
+    /// assert_eq!(values[1].0.get("b"), &Value::from(2u64));
+    /// assert_eq!(&data[values[2].1.clone()], "[3]");
+    /// ```
+    pub fn stream_from_str(json: &'ctx str) -> ValueStream<'ctx> {
+        ValueStream {
+            parser: Parser::new(json),
+            failed: false,
+        }
+    }
+}
+
+/// An iterator over a sequence of JSON values in a buffer, created by
+/// [`Value::stream_from_str`].
+///
+/// Yields each value together with its byte range in the buffer.
+pub struct ValueStream<'ctx> {
+    parser: Parser<'ctx>,
+    failed: bool,
+}
+
+impl ValueStream<'_> {
+    /// Handles duplicate keys in objects according to `policy`, see
+    /// [`from_str_with_policy`](crate::from_str_with_policy).
+    pub fn with_policy(self, policy: DuplicateKeyPolicy) -> Self {
+        ValueStream {
+            parser: self.parser.with_policy(policy),
+            ..self
+        }
+    }
+
+    /// The byte offset in the buffer up to which values have been parsed.
+    pub fn byte_offset(&self) -> usize {
+        self.parser.position()
+    }
+}
+
+impl<'ctx> Iterator for ValueStream<'ctx> {
+    type Item = Result<(Value<'ctx>, Range<usize>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.parser.at_end() {
+            return None;
+        }
+        let start = self.parser.position();
+        let value = self.parser.parse_value().and_then(|value| {
+            if let Value::Null | Value::Bool(_) | Value::Number(_) = value {
+                self.parser.end_of_stream_value()?;
+            }
+            Ok(value)
+        });
+        match value {
+            Ok(value) => Some(Ok((value, start..self.parser.position()))),
+            Err(err) => {
+                self.failed = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranges(json: &str) -> Vec<&str> {
+        Value::stream_from_str(json)
+            .map(|item| &json[item.unwrap().1])
+            .collect()
+    }
+
+    #[test]
+    fn test_stream() {
+        assert_eq!(
+            ranges(" {\"a\":1}{\"b\":2}\n[3]\"x\"1 2 true null[]"),
+            vec![
+                "{\"a\":1}",
+                "{\"b\":2}",
+                "[3]",
+                "\"x\"",
+                "1",
+                "2",
+                "true",
+                "null",
+                "[]"
+            ]
+        );
+        assert_eq!(ranges(""), Vec::<&str>::new());
+        assert_eq!(ranges(" \n "), Vec::<&str>::new());
+        assert_eq!(ranges("12"), vec!["12"]);
+        assert_eq!(ranges("1[2]"), vec!["1", "[2]"]);
+    }
+
+    #[test]
+    fn test_stream_values_borrow() {
+        let json = r#"{"a": "b"} ["c"]"#;
+        let values: Vec<Value> = Value::stream_from_str(json)
+            .map(|item| item.unwrap().0)
+            .collect();
+        assert_eq!(values[0].get("a"), &Value::Str("b".into()));
+        assert_eq!(values[1].get(0), &Value::Str("c".into()));
+    }
+
+    #[test]
+    fn test_stream_errors() {
+        let mut stream = Value::stream_from_str("[1] 1true [2]");
+        assert!(stream.next().unwrap().is_ok());
+        let err = stream.next().unwrap().unwrap_err();
+        assert_eq!((err.message(), err.column()), ("trailing characters", 6));
+        assert!(stream.next().is_none());
+
+        let mut stream = Value::stream_from_str("[1]\n[2,]");
+        assert!(stream.next().unwrap().is_ok());
+        assert_eq!(stream.byte_offset(), 3);
+        let err = stream.next().unwrap().unwrap_err();
+        assert_eq!((err.line(), err.column()), (2, 4));
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    fn test_stream_policy() {
+        let json = r#"{"a":1,"a":2} {"b":1,"b":2}"#;
+        let values: Vec<Value> = Value::stream_from_str(json)
+            .with_policy(DuplicateKeyPolicy::KeepLast)
+            .map(|item| item.unwrap().0)
+            .collect();
+        assert_eq!(values[0], crate::from_str(r#"{"a":2}"#).unwrap());
+        assert_eq!(values[1], crate::from_str(r#"{"b":2}"#).unwrap());
+
+        let mut stream = Value::stream_from_str(json).with_policy(DuplicateKeyPolicy::Error);
+        let err = stream.next().unwrap().unwrap_err();
+        assert_eq!(err.message(), "duplicate key `a`");
+        assert!(stream.next().is_none());
+    }
+}
@@ -2,9 +2,10 @@ use core::fmt;
 use core::hash::Hash;
 use std::borrow::Cow;
 use std::fmt::{Debug, Display};
+use std::ops;
 
 use crate::index::Index;
-use crate::num::{Number, N};
+use crate::num::Number;
 pub use crate::object_vec::ObjectAsVec;
 
 /// Represents any valid JSON value.
@@ -49,7 +50,7 @@ pub enum Value<'ctx> {
     /// #
     /// let v = Value::Number(12.5.into());
     /// ```
-    Number(Number),
+    Number(Number<'ctx>),
 
     /// Represents a JSON string.
     ///
@@ -109,11 +110,170 @@ impl<'ctx> Value<'ctx> {
     /// assert_eq!(data.get("a").get("b"), &Value::Null);
     /// ```
     #[inline]
-    pub fn get<I: Index<'ctx>>(&'ctx self, index: I) -> &'ctx Value<'ctx> {
+    pub fn get<I: Index>(&self, index: I) -> &Value<'ctx> {
         static NULL: Value = Value::Null;
         index.index_into(self).unwrap_or(&NULL)
     }
 
+    /// Mutably index into a `serde_json_borrow::Value` using the syntax `value.get_mut(0)` or
+    /// `value.get_mut("k")`.
+    ///
+    /// Returns `None` if the type of `self` does not match the type of the
+    /// index, for example if the index is a string and `self` is an array or a
+    /// number. Also returns `None` if the given key does not exist in the map
+    /// or the given index is not within the bounds of the array.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use serde_json_borrow::Value;
+    /// #
+    /// let mut data: Value = serde_json::from_str(r#"{"x": {"y": ["z", "zz"]}}"#).unwrap();
+    ///
+    /// *data.get_mut("x").unwrap().get_mut("y").unwrap().get_mut(0).unwrap() = Value::Null;
+    /// assert_eq!(data.get("x").get("y").get(0), &Value::Null);
+    /// assert_eq!(data.get_mut("a"), None);
+    /// ```
+    #[inline]
+    pub fn get_mut<I: Index>(&mut self, index: I) -> Option<&mut Value<'ctx>> {
+        index.index_into_mut(self)
+    }
+
+    /// Takes the value out of the `Value`, leaving a `Null` in its place.
+    ///
+    /// ```
+    /// # use serde_json_borrow::Value;
+    /// #
+    /// let mut data: Value = serde_json::from_str(r#"{"x": "y"}"#).unwrap();
+    /// assert_eq!(data.get_mut("x").unwrap().take(), Value::Str("y".into()));
+    /// assert_eq!(data.get("x"), &Value::Null);
+    /// ```
+    #[inline]
+    pub fn take(&mut self) -> Value<'ctx> {
+        std::mem::take(self)
+    }
+
+    /// Looks up a value by a JSON Pointer.
+    ///
+    /// JSON Pointer defines a string syntax for identifying a specific value
+    /// within a JavaScript Object Notation (JSON) document.
+    ///
+    /// A Pointer is a Unicode string with the reference tokens separated by `/`.
+    /// Inside tokens `/` is replaced by `~1` and `~` is replaced by `~0`. The
+    /// addressed value is returned and if there is no such value `None` is
+    /// returned.
+    ///
+    /// For more information read [RFC6901](https://tools.ietf.org/html/rfc6901).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use serde_json_borrow::Value;
+    /// #
+    /// let data: Value = serde_json::from_str(r#"{"x": {"y": ["z", "zz"]}, "a/b": 1}"#).unwrap();
+    ///
+    /// assert_eq!(data.pointer("/x/y/1"), Some(&Value::Str("zz".into())));
+    /// assert_eq!(data.pointer("/a~1b"), Some(&Value::Number(1u64.into())));
+    /// assert_eq!(data.pointer("/a/b"), None);
+    /// ```
+    pub fn pointer(&self, pointer: &str) -> Option<&Value<'ctx>> {
+        if pointer.is_empty() {
+            return Some(self);
+        }
+        if !pointer.starts_with('/') {
+            return None;
+        }
+        pointer
+            .split('/')
+            .skip(1)
+            .map(unescape_pointer_token)
+            .try_fold(self, |target, token| match target {
+                Value::Object(map) => map.get(&token),
+                Value::Array(list) => parse_pointer_index(&token).and_then(|i| list.get(i)),
+                _ => None,
+            })
+    }
+
+    /// Looks up a value by a JSON Pointer and returns a mutable reference to
+    /// that value.
+    ///
+    /// See [`Value::pointer`] for the syntax of JSON Pointers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use serde_json_borrow::Value;
+    /// #
+    /// let mut data: Value = serde_json::from_str(r#"{"x": 1.0, "y": [2.0]}"#).unwrap();
+    ///
+    /// if let Some(value) = data.pointer_mut("/y/0") {
+    ///     *value = Value::Str("two".into());
+    /// }
+    /// assert_eq!(data.pointer("/y/0"), Some(&Value::Str("two".into())));
+    /// ```
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut Value<'ctx>> {
+        if pointer.is_empty() {
+            return Some(self);
+        }
+        if !pointer.starts_with('/') {
+            return None;
+        }
+        pointer
+            .split('/')
+            .skip(1)
+            .map(unescape_pointer_token)
+            .try_fold(self, |target, token| match target {
+                Value::Object(map) => map.get_mut(&token),
+                Value::Array(list) => {
+                    parse_pointer_index(&token).and_then(move |i| list.get_mut(i))
+                }
+                _ => None,
+            })
+    }
+
+    /// Converts the value into a value that owns all its data, and is no longer tied to the
+    /// lifetime of the input.
+    ///
+    /// All borrowed strings and keys are copied, owned data is moved. Numbers lose their text,
+    /// see [`Number::into_owned`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use serde_json_borrow::Value;
+    /// #
+    /// fn parse(json: String) -> Value<'static> {
+    ///     let value: Value = serde_json::from_str(&json).unwrap();
+    ///     value.into_owned()
+    /// }
+    ///
+    /// let value = parse(r#"{"name": "John"}"#.to_string());
+    /// assert_eq!(value.get("name"), &Value::Str("John".into()));
+    /// ```
+    pub fn into_owned(self) -> Value<'static> {
+        match self {
+            Value::Null => Value::Null,
+            Value::Bool(b) => Value::Bool(b),
+            Value::Number(n) => Value::Number(n.into_owned()),
+            Value::Str(s) => Value::Str(Cow::Owned(s.into_owned())),
+            Value::Array(arr) => Value::Array(arr.into_iter().map(Value::into_owned).collect()),
+            Value::Object(obj) => Value::Object(obj.into_owned()),
+        }
+    }
+
+    /// Copies the value into a value that owns all its data, like [`Value::into_owned`], but
+    /// without consuming `self`.
+    pub fn to_owned_value(&self) -> Value<'static> {
+        match self {
+            Value::Null => Value::Null,
+            Value::Bool(b) => Value::Bool(*b),
+            Value::Number(n) => Value::Number(n.into_owned()),
+            Value::Str(s) => Value::Str(Cow::Owned(s.to_string())),
+            Value::Array(arr) => Value::Array(arr.iter().map(Value::to_owned_value).collect()),
+            Value::Object(obj) => Value::Object(obj.to_owned_object()),
+        }
+    }
+
     /// Returns true if `Value` is Value::Null.
     pub fn is_null(&self) -> bool {
         matches!(self, Value::Null)
@@ -204,6 +364,41 @@ impl<'ctx> Value<'ctx> {
         }
     }
 
+    /// If the Value is an Array, returns the associated mutable Vec. Returns None otherwise.
+    pub fn as_array_mut(&mut self) -> Option<&mut Vec<Value<'ctx>>> {
+        match self {
+            Value::Array(arr) => Some(arr),
+            _ => None,
+        }
+    }
+
+    /// If the Value is an Object, returns the associated mutable Object. Returns None otherwise.
+    pub fn as_object_mut(&mut self) -> Option<&mut ObjectAsVec<'ctx>> {
+        match self {
+            Value::Object(obj) => Some(obj),
+            _ => None,
+        }
+    }
+
+    /// If the Value is a String, returns the associated mutable `Cow<str>`. Returns None
+    /// otherwise.
+    ///
+    /// The string may borrow from the input, use [`Cow::to_mut`] to modify it in place.
+    pub fn as_str_mut(&mut self) -> Option<&mut Cow<'ctx, str>> {
+        match self {
+            Value::Str(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// If the Value is a Number, returns the associated mutable Number. Returns None otherwise.
+    pub fn as_number_mut(&mut self) -> Option<&mut Number<'ctx>> {
+        match self {
+            Value::Number(n) => Some(n),
+            _ => None,
+        }
+    }
+
     /// If the Value is a Boolean, returns the associated bool. Returns None otherwise.
     pub fn as_bool(&self) -> Option<bool> {
         match self {
@@ -236,6 +431,22 @@ impl<'ctx> Value<'ctx> {
         }
     }
 
+    /// If the Value is an integer, represent it as i128 if possible. Returns None otherwise.
+    pub fn as_i128(&self) -> Option<i128> {
+        match self {
+            Value::Number(n) => n.as_i128(),
+            _ => None,
+        }
+    }
+
+    /// If the Value is an integer, represent it as u128 if possible. Returns None otherwise.
+    pub fn as_u128(&self) -> Option<u128> {
+        match self {
+            Value::Number(n) => n.as_u128(),
+            _ => None,
+        }
+    }
+
     /// If the Value is a number, represent it as f64 if possible. Returns None otherwise.
     pub fn as_f64(&self) -> Option<f64> {
         match self {
@@ -245,6 +456,86 @@ impl<'ctx> Value<'ctx> {
     }
 }
 
+/// Replaces `~1` with `/` and `~0` with `~` in a JSON Pointer reference token.
+fn unescape_pointer_token(token: &str) -> Cow<'_, str> {
+    if token.contains('~') {
+        Cow::Owned(token.replace("~1", "/").replace("~0", "~"))
+    } else {
+        Cow::Borrowed(token)
+    }
+}
+
+/// Parses an array index of a JSON Pointer. Leading zeros and signs are not allowed.
+fn parse_pointer_index(token: &str) -> Option<usize> {
+    if token.starts_with('+') || (token.starts_with('0') && token.len() != 1) {
+        return None;
+    }
+    token.parse().ok()
+}
+
+/// Index into a `serde_json_borrow::Value` using the syntax `value[0]` or `value["k"]`.
+///
+/// Returns `Value::Null` if the type of `self` does not match the type of the index, or if the
+/// key or index does not exist, like [`Value::get`].
+///
+/// # Examples
+///
+/// ```
+/// # use serde_json_borrow::Value;
+/// #
+/// let data: Value = serde_json::from_str(r#"{"x": {"y": ["z", "zz"]}}"#).unwrap();
+///
+/// assert_eq!(data["x"]["y"][0], Value::Str("z".into()));
+/// assert_eq!(data["x"]["y"][2], Value::Null);
+/// assert_eq!(data["a"]["b"], Value::Null);
+/// ```
+impl<'ctx, I: Index> ops::Index<I> for Value<'ctx> {
+    type Output = Value<'ctx>;
+
+    #[inline]
+    fn index(&self, index: I) -> &Value<'ctx> {
+        self.get(index)
+    }
+}
+
+/// Write into a `serde_json_borrow::Value` using the syntax `value[0] = ...` or
+/// `value["k"] = ...`.
+///
+/// If the index is a number, the value must be an array of length bigger than the index.
+/// Indexing into a value that is not an array or an array that is too small will panic.
+///
+/// If the index is a string, the value must be an object or null which is treated like an
+/// empty object. If the key is not already present in the object, it will be inserted with a
+/// value of null. Indexing into a value that is neither an object nor null will panic.
+///
+/// # Examples
+///
+/// ```
+/// # use serde_json_borrow::Value;
+/// #
+/// let mut data: Value = serde_json::from_str(r#"{"x": 0}"#).unwrap();
+///
+/// // replace an existing key
+/// data["x"] = Value::from(1u64);
+/// // insert a new key
+/// data["y"] = Value::from(vec![false, false, false]);
+/// // replace an array value
+/// data["y"][0] = Value::Bool(true);
+/// // inserted a deeply nested key
+/// data["a"]["b"]["c"]["d"] = Value::Bool(true);
+///
+/// assert_eq!(
+///     serde_json::to_string(&data).unwrap(),
+///     r#"{"x":1,"y":[true,false,false],"a":{"b":{"c":{"d":true}}}}"#
+/// );
+/// ```
+impl<I: Index> ops::IndexMut<I> for Value<'_> {
+    #[inline]
+    fn index_mut(&mut self, index: I) -> &mut Self {
+        index.index_or_insert(self)
+    }
+}
+
 impl From<bool> for Value<'_> {
     fn from(val: bool) -> Self {
         Value::Bool(val)
@@ -280,11 +571,7 @@ impl Debug for Value<'_> {
         match self {
             Value::Null => formatter.write_str("Null"),
             Value::Bool(boolean) => write!(formatter, "Bool({})", boolean),
-            Value::Number(number) => match number.n {
-                N::PosInt(n) => write!(formatter, "Number({:?})", n),
-                N::NegInt(n) => write!(formatter, "Number({:?})", n),
-                N::Float(n) => write!(formatter, "Number({:?})", n),
-            },
+            Value::Number(number) => Debug::fmt(number, formatter),
             Value::Str(string) => write!(formatter, "Str({:?})", string),
             Value::Array(vec) => {
                 formatter.write_str("Array ")?;
@@ -317,9 +604,22 @@ impl From<i64> for Value<'_> {
     }
 }
 
+impl From<u128> for Value<'_> {
+    fn from(val: u128) -> Self {
+        Value::Number(val.into())
+    }
+}
+
+impl From<i128> for Value<'_> {
+    fn from(val: i128) -> Self {
+        Value::Number(val.into())
+    }
+}
+
+/// Like in serde_json, NaN and infinite values are converted to `Null`.
 impl From<f64> for Value<'_> {
     fn from(val: f64) -> Self {
-        Value::Number(val.into())
+        Number::from_f64(val).map_or(Value::Null, Value::Number)
     }
 }
 
@@ -438,4 +738,99 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn pointer_test() {
+        let data = r#"{
+            "foo": ["bar", "baz"],
+            "": 0,
+            "a/b": 1,
+            "c%d": 2,
+            "e^f": 3,
+            "g|h": 4,
+            "i\\j": 5,
+            "k\"l": 6,
+            " ": 7,
+            "m~n": 8
+        }"#;
+        let expected: serde_json::Value = serde_json::from_str(data).unwrap();
+        let value: Value = (&expected).into();
+        let pointers = [
+            "", "/foo", "/foo/0", "/", "/a~1b", "/c%d", "/e^f", "/g|h", "/i\\j", "/k\"l", "/ ",
+            "/m~0n", "/foo/1", "/foo/2", "/foo/01", "/foo/+1", "/foo/-", "/a/b", "foo", "/missing",
+            "/foo/0/x",
+        ];
+        for pointer in pointers {
+            assert_eq!(
+                value.pointer(pointer).map(serde_json::Value::from),
+                expected.pointer(pointer).cloned(),
+                "{pointer}"
+            );
+        }
+    }
+
+    #[test]
+    fn pointer_mut_test() {
+        let mut value: Value = serde_json::from_str(r#"{"x": {"a~b": [1, 2]}}"#).unwrap();
+        *value.pointer_mut("/x/a~0b/1").unwrap() = Value::Bool(true);
+        assert_eq!(value.pointer("/x/a~0b/1"), Some(&Value::Bool(true)));
+        *value.pointer_mut("").unwrap() = Value::Null;
+        assert_eq!(value, Value::Null);
+        assert!(value.pointer_mut("/x").is_none());
+    }
+
+    #[test]
+    fn mut_accessors_test() {
+        let mut value: Value =
+            serde_json::from_str(r#"{"arr": [1], "text": "a", "num": 1}"#).unwrap();
+        value
+            .get_mut("arr")
+            .unwrap()
+            .as_array_mut()
+            .unwrap()
+            .push(Value::Null);
+        value
+            .get_mut("text")
+            .unwrap()
+            .as_str_mut()
+            .unwrap()
+            .to_mut()
+            .push('b');
+        *value.get_mut("num").unwrap().as_number_mut().unwrap() = 2u64.into();
+        value
+            .as_object_mut()
+            .unwrap()
+            .insert("new", Value::Bool(true));
+        assert!(value.get_mut("arr").unwrap().as_object_mut().is_none());
+
+        let expected: Value =
+            serde_json::from_str(r#"{"arr": [1, null], "text": "ab", "num": 2, "new": true}"#)
+                .unwrap();
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    fn index_ops_test() {
+        let mut value: Value = serde_json::from_str(r#"{"a": [1, {"b": "c"}]}"#).unwrap();
+        assert_eq!(value["a"][1]["b"], Value::Str("c".into()));
+        assert_eq!(value["a"][2], Value::Null);
+        assert_eq!(value["a"]["b"], Value::Null);
+        let key = "a".to_string();
+        assert_eq!(value[&key][0], Value::Number(1u64.into()));
+
+        value["a"][1]["b"] = Value::Bool(true);
+        value["a"][1]["new"] = Value::Bool(false);
+        value["x"]["y"] = Value::Null;
+        let expected: Value =
+            serde_json::from_str(r#"{"a": [1, {"b": true, "new": false}], "x": {"y": null}}"#)
+                .unwrap();
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot access index 0 of JSON object")]
+    fn index_mut_wrong_type_test() {
+        let mut value = Value::Object(Default::default());
+        value[0] = Value::Null;
+    }
 }